@@ -0,0 +1,7 @@
+//! Code generators that turn a parsed tree into Rust code.
+//!
+//! These are meant to be called from a proc macro. The generated code refers
+//! to runtime support in this crate, so crates using it need to depend on
+//! `snax` too.
+
+pub mod ssr;
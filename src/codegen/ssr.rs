@@ -0,0 +1,567 @@
+//! Server-side rendering: lowers a tree into code that writes HTML to a
+//! [`std::fmt::Write`].
+//!
+//! A proc macro would use [`lower`] like this:
+//!
+//! ```
+//! use quote::quote;
+//!
+//! let item = snax::parse(quote!(
+//!     <p class="greeting">"Hello, " { name } "!"</p>
+//! )).unwrap();
+//!
+//! let code = snax::codegen::ssr::lower(&item, quote!(&mut output)).unwrap();
+//! ```
+//!
+//! The generated expression evaluates to a `std::fmt::Result`. With `output`
+//! being a `String`, it behaves like:
+//!
+//! ```
+//! # use std::fmt::Write;
+//! # use snax::codegen::ssr::Render;
+//! # fn main() -> std::fmt::Result {
+//! # let mut output = String::new();
+//! # let name = "<World>";
+//! output.write_str("<p class=\"greeting\">Hello, ")?;
+//! Render::render(&(name), &mut output)?;
+//! output.write_str("!</p>")?;
+//! # assert_eq!(output, "<p class=\"greeting\">Hello, &lt;World&gt;!</p>");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Errors stop the output early by breaking out of a labeled block, so crates
+//! using the generated code need Rust 1.65 or newer.
+//!
+//! [`std::fmt::Write`]: https://doc.rust-lang.org/std/fmt/trait.Write.html
+//! [`lower`]: fn.lower.html
+
+use std::fmt::{self, Write};
+
+use proc_macro2::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+
+use crate::{
+    compile_error,
+    html::{escape, VOID_ELEMENTS},
+    literal::literal_text,
+    value_expression,
+    AttributeValue,
+    SnaxAttribute,
+    SnaxExpression,
+    SnaxItem,
+    SnaxName,
+    SnaxPath,
+};
+
+/// Lowers a tree into an expression that writes it as HTML to `writer`, which
+/// must be an expression giving a mutable reference to a `std::fmt::Write`,
+/// like `&mut output` or a `&mut fmt::Formatter`.
+///
+/// Literals are escaped at macro time, and runs of static markup are merged
+/// into a single `write_str` call. Blocks are written through [`Render`] as
+/// content, and as attribute values when used as one, along with paths like
+/// `Kind::Linear`. Attributes set to `true` are written without a value, and
+/// attributes set to `false` are left out. Attribute spreads are
+/// written through [`RenderAttributes`]. Blocks should contain a single
+/// expression, which is borrowed rather than moved.
+///
+/// Control flow is lowered into the matching Rust `if`, `for`, or `match`.
+///
+/// Blocks with markup embedded in them, from [`parse_embedded`], are written
+/// through [`RenderMarkup`], with each piece of markup turned into a
+/// [`Markup`] value that borrows the variables it uses. These blocks are moved
+/// rather than borrowed, so that iterators of markup can be consumed. Markup isn't allowed in attribute
+/// values, embedded or not, since HTML attributes can only hold text.
+///
+/// Component tags, which have a path like `ui::Button` as their name or have
+/// generic arguments, have no HTML representation and are rejected.
+///
+/// [`Render`]: trait.Render.html
+/// [`RenderAttributes`]: trait.RenderAttributes.html
+/// [`RenderMarkup`]: trait.RenderMarkup.html
+/// [`Markup`]: struct.Markup.html
+/// [`parse_embedded`]: ../../fn.parse_embedded.html
+pub fn lower(item: &SnaxItem, writer: TokenStream) -> Result<TokenStream, LowerError> {
+    let mut lowering = Lowering {
+        body: TokenStream::new(),
+        pending: String::new(),
+    };
+
+    lowering.item(item)?;
+
+    let mut block = tokens("let __snax_writer: &mut dyn ::core::fmt::Write =");
+    block.extend(writer);
+    block.extend(tokens(";"));
+    block.extend(lowering.finish());
+
+    Ok(group(Delimiter::Brace, block).into())
+}
+
+/// An error returned by [`lower`] for markup that can't be rendered to HTML.
+///
+/// [`lower`]: fn.lower.html
+#[derive(Debug, Clone)]
+pub enum LowerError {
+    /// A component tag was used.
+    Component {
+        name: SnaxPath,
+        span: Span,
+    },
+
+    /// Markup was used in the value of an attribute.
+    MarkupAttribute {
+        name: SnaxName,
+        span: Span,
+    },
+}
+
+impl LowerError {
+    /// Returns the span of the markup that couldn't be lowered.
+    pub fn span(&self) -> Span {
+        match self {
+            LowerError::Component { span, .. } => *span,
+            LowerError::MarkupAttribute { span, .. } => *span,
+        }
+    }
+
+    /// Creates a `compile_error!` invocation describing this error.
+    pub fn to_compile_error(&self) -> TokenStream {
+        compile_error(&self.to_string(), self.span())
+    }
+}
+
+impl fmt::Display for LowerError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LowerError::Component { name, .. } => {
+                write!(formatter, "component `<{}>` can't be rendered to HTML", name)
+            },
+            LowerError::MarkupAttribute { name, .. } => {
+                write!(formatter, "attribute `{}` contains markup, which can't be rendered to HTML", name)
+            },
+        }
+    }
+}
+
+impl std::error::Error for LowerError {}
+
+/// A value that can be written as escaped HTML text or as an escaped attribute
+/// value. This is implemented for every type that implements `Display`.
+///
+/// To write markup that's already been escaped, wrap it in [`Raw`].
+///
+/// [`Raw`]: struct.Raw.html
+pub trait Render {
+    fn render(&self, writer: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+impl<T: fmt::Display + ?Sized> Render for T {
+    fn render(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        write!(Escaper(writer), "{}", self)
+    }
+}
+
+/// Writes its contents without escaping them.
+#[derive(Debug, Clone, Copy)]
+pub struct Raw<T>(pub T);
+
+impl<T: fmt::Display> Render for Raw<T> {
+    fn render(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        write!(writer, "{}", self.0)
+    }
+}
+
+/// Markup embedded in a Rust expression. The code generated by [`lower`]
+/// creates these, so that each piece of markup in an expression has the same
+/// type no matter where it came from.
+///
+/// Markup is rendered as soon as it's created, so the values it uses only need
+/// to be borrowed until then. This lets markup returned from a closure, like
+/// the one given to `map`, use both the closure's parameters and variables from
+/// outside of it.
+///
+/// [`lower`]: fn.lower.html
+#[derive(Debug)]
+pub struct Markup(Result<String, fmt::Error>);
+
+impl Markup {
+    pub fn new<F>(render: F) -> Self
+        where F: FnOnce(&mut dyn fmt::Write) -> fmt::Result
+    {
+        let mut output = String::new();
+        Markup(render(&mut output).map(|()| output))
+    }
+}
+
+impl Render for Markup {
+    fn render(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        match &self.0 {
+            Ok(output) => writer.write_str(output),
+            Err(error) => Err(*error),
+        }
+    }
+}
+
+/// The result of a block with markup embedded in it, which is either a single
+/// [`Markup`] or anything that can be iterated over to get them, like an
+/// `Option` or an iterator from `map`.
+///
+/// [`Markup`]: struct.Markup.html
+pub trait RenderMarkup {
+    fn render_markup(self, writer: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+impl RenderMarkup for Markup {
+    fn render_markup(self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        self.render(writer)
+    }
+}
+
+impl<I: IntoIterator> RenderMarkup for I
+    where I::Item: RenderMarkup
+{
+    fn render_markup(self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        for markup in self {
+            markup.render_markup(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A set of attributes that can be spread into a tag with `{..attributes}`.
+///
+/// Each attribute is written with a leading space, like ` name="value"`.
+pub trait RenderAttributes {
+    fn render_attributes(&self, writer: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+impl<K: fmt::Display, V: Render> RenderAttributes for [(K, V)] {
+    fn render_attributes(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        for (name, value) in self {
+            write!(writer, " {}=\"", name)?;
+            value.render(writer)?;
+            writer.write_char('"')?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: fmt::Display, V: Render, const N: usize> RenderAttributes for [(K, V); N] {
+    fn render_attributes(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        self[..].render_attributes(writer)
+    }
+}
+
+impl<K: fmt::Display, V: Render> RenderAttributes for Vec<(K, V)> {
+    fn render_attributes(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        self[..].render_attributes(writer)
+    }
+}
+
+/// Escapes everything written through it for use in both text and
+/// double-quoted attribute values.
+struct Escaper<'a>(&'a mut dyn fmt::Write);
+
+impl fmt::Write for Escaper<'_> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        let mut escaped = String::with_capacity(text.len());
+        escape(text, true, &mut escaped);
+        self.0.write_str(&escaped)
+    }
+}
+
+struct Lowering {
+    /// The statements generated so far.
+    body: TokenStream,
+
+    /// Static markup that hasn't been written out yet.
+    pending: String,
+}
+
+impl Lowering {
+    /// Finishes lowering, returning a labeled block that can be broken out
+    /// of with an error and that evaluates to `Ok(())` otherwise.
+    fn finish(mut self) -> TokenStream {
+        self.flush();
+
+        let mut body = self.body;
+        body.extend(tokens("::core::result::Result::Ok(())"));
+
+        let mut block = tokens("'__snax:");
+        block.extend(Some(group(Delimiter::Brace, body)));
+        block
+    }
+
+    fn item(&mut self, item: &SnaxItem) -> Result<(), LowerError> {
+        match item {
+            SnaxItem::Tag(tag) => {
+                let name = element_name(&tag.name, tag.generics.is_some(), tag.open_span)?;
+
+                self.open_tag(&name, &tag.attributes)?;
+
+                for child in &tag.children {
+                    self.item(child)?;
+                }
+
+                self.close_tag(&name);
+            },
+            SnaxItem::SelfClosingTag(tag) => {
+                let name = element_name(&tag.name, tag.generics.is_some(), tag.span)?;
+
+                self.open_tag(&name, &tag.attributes)?;
+
+                if !VOID_ELEMENTS.contains(&name.as_str()) {
+                    self.close_tag(&name);
+                }
+            },
+            SnaxItem::Fragment(fragment) => {
+                for child in &fragment.children {
+                    self.item(child)?;
+                }
+            },
+            SnaxItem::Content(content) => self.content(content),
+            SnaxItem::Expression(expression) => self.expression(expression)?,
+            SnaxItem::If(node) => {
+                let mut code = tokens("if");
+                code.extend(node.condition.clone());
+                code.extend(Some(self.branch(&node.then_branch)?));
+
+                if let Some(else_branch) = &node.else_branch {
+                    code.extend(tokens("else"));
+                    code.extend(Some(self.branch(else_branch)?));
+                }
+
+                self.control_flow(code);
+            },
+            SnaxItem::For(node) => {
+                let mut code = tokens("for");
+                code.extend(node.pattern.clone());
+                code.extend(tokens("in"));
+                code.extend(node.iterator.clone());
+                code.extend(Some(self.branch(&node.body)?));
+
+                self.control_flow(code);
+            },
+            SnaxItem::Match(node) => {
+                let mut arms = TokenStream::new();
+
+                for arm in &node.arms {
+                    arms.extend(arm.pattern.clone());
+
+                    if let Some(guard) = &arm.guard {
+                        arms.extend(tokens("if"));
+                        arms.extend(guard.clone());
+                    }
+
+                    arms.extend(tokens("=>"));
+                    arms.extend(Some(self.branch(&arm.body)?));
+                }
+
+                let mut code = tokens("match");
+                code.extend(node.expression.clone());
+                code.extend(Some(group(Delimiter::Brace, arms)));
+
+                self.control_flow(code);
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Lowers the items in one branch of a control-flow item into a block.
+    fn branch(&self, items: &[SnaxItem]) -> Result<TokenTree, LowerError> {
+        let mut lowering = Lowering {
+            body: TokenStream::new(),
+            pending: String::new(),
+        };
+
+        for item in items {
+            lowering.item(item)?;
+        }
+
+        lowering.flush();
+
+        Ok(group(Delimiter::Brace, lowering.body))
+    }
+
+    /// Emits a Rust `if`, `for`, or `match` after any pending static markup.
+    fn control_flow(&mut self, code: TokenStream) {
+        self.flush();
+        self.body.extend(code);
+    }
+
+    fn open_tag(&mut self, name: &str, attributes: &[SnaxAttribute]) -> Result<(), LowerError> {
+        self.pending.push('<');
+        self.pending.push_str(name);
+
+        for attribute in attributes {
+            match attribute {
+                SnaxAttribute::Simple { name, value: AttributeValue::Bool { value, .. } } => {
+                    if *value {
+                        self.pending.push(' ');
+                        self.pending.push_str(&name.to_string());
+                    }
+                },
+                SnaxAttribute::Simple { name, value } => {
+                    self.pending.push(' ');
+                    self.pending.push_str(&name.to_string());
+                    self.pending.push_str("=\"");
+
+                    match value.literal_text() {
+                        Some(text) => escape(&text, true, &mut self.pending),
+                        None => self.call("::snax::codegen::ssr::Render::render", value.expression()),
+                    }
+
+                    self.pending.push('"');
+                },
+                SnaxAttribute::Flag { name } => {
+                    self.pending.push(' ');
+                    self.pending.push_str(&name.to_string());
+                },
+                SnaxAttribute::Spread { value, .. } => {
+                    self.call("::snax::codegen::ssr::RenderAttributes::render_attributes", value.clone());
+                },
+                SnaxAttribute::Markup { name, .. } | SnaxAttribute::Expression { name, .. } => {
+                    return Err(LowerError::MarkupAttribute {
+                        name: name.clone(),
+                        span: attribute.span(),
+                    });
+                },
+            }
+        }
+
+        self.pending.push('>');
+        Ok(())
+    }
+
+    fn close_tag(&mut self, name: &str) {
+        self.pending.push_str("</");
+        self.pending.push_str(name);
+        self.pending.push('>');
+    }
+
+    /// Writes content, escaping it at macro time if it's a literal.
+    fn content(&mut self, content: &TokenTree) {
+        if let TokenTree::Literal(literal) = content {
+            if let Some(text) = literal_text(literal) {
+                escape(&text, false, &mut self.pending);
+                return;
+            }
+        }
+
+        self.call("::snax::codegen::ssr::Render::render", value_expression(content));
+    }
+
+    /// Writes a block with markup embedded in it, replacing each piece of
+    /// markup with a `Markup` that renders it.
+    fn expression(&mut self, expression: &SnaxExpression) -> Result<(), LowerError> {
+        let mut error = None;
+
+        let value = expression.substitute(|item| match markup(item) {
+            Ok(markup) => markup,
+            Err(item_error) => {
+                error.get_or_insert(item_error);
+                TokenStream::new()
+            },
+        });
+
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        let mut block = Lowering {
+            body: tokens("let __snax_markup ="),
+            pending: String::new(),
+        };
+
+        block.body.extend(Some(value));
+        block.body.extend(tokens(";"));
+        block.statement(tokens("::snax::codegen::ssr::RenderMarkup::render_markup(__snax_markup, &mut *__snax_writer)"));
+
+        self.flush();
+        self.body.extend(Some(group(Delimiter::Brace, block.body)));
+
+        Ok(())
+    }
+
+    /// Emits `function(&(argument), &mut *__snax_writer)` after any pending
+    /// static markup.
+    fn call(&mut self, function: &str, argument: TokenStream) {
+        let mut arguments = tokens("&");
+        arguments.extend(Some(group(Delimiter::Parenthesis, argument)));
+        arguments.extend(tokens(", &mut *__snax_writer"));
+
+        let mut call = tokens(function);
+        call.extend(Some(group(Delimiter::Parenthesis, arguments)));
+
+        self.flush();
+        self.statement(call);
+    }
+
+    /// Emits a `write_str` call for any pending static markup.
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let text = Literal::string(&std::mem::take(&mut self.pending));
+
+        let mut arguments = tokens("&mut *__snax_writer,");
+        arguments.extend(Some(TokenTree::Literal(text)));
+
+        let mut call = tokens("::core::fmt::Write::write_str");
+        call.extend(Some(group(Delimiter::Parenthesis, arguments)));
+
+        self.statement(call);
+    }
+
+    /// Emits a call returning `fmt::Result`, breaking out with the error if
+    /// it fails.
+    fn statement(&mut self, call: TokenStream) {
+        self.body.extend(tokens("if let ::core::result::Result::Err(error) ="));
+        self.body.extend(call);
+        self.body.extend(Some(group(
+            Delimiter::Brace,
+            tokens("break '__snax ::core::result::Result::Err(error);"),
+        )));
+    }
+}
+
+/// Lowers a piece of embedded markup into a `Markup` holding a closure that
+/// writes it.
+fn markup(item: &SnaxItem) -> Result<TokenStream, LowerError> {
+    let mut lowering = Lowering {
+        body: TokenStream::new(),
+        pending: String::new(),
+    };
+
+    lowering.item(item)?;
+
+    let mut closure = tokens("|__snax_writer: &mut dyn ::core::fmt::Write| -> ::core::fmt::Result");
+    closure.extend(Some(group(Delimiter::Brace, lowering.finish())));
+
+    let mut code = tokens("::snax::codegen::ssr::Markup::new");
+    code.extend(Some(group(Delimiter::Parenthesis, closure)));
+
+    Ok(code)
+}
+
+fn element_name(name: &SnaxPath, has_generics: bool, span: Span) -> Result<String, LowerError> {
+    match name.as_name() {
+        Some(element) if !has_generics => Ok(element.to_string()),
+        _ => Err(LowerError::Component {
+            name: name.clone(),
+            span,
+        }),
+    }
+}
+
+fn tokens(source: &str) -> TokenStream {
+    source.parse().expect("generated code should always tokenize")
+}
+
+fn group(delimiter: Delimiter, stream: TokenStream) -> TokenTree {
+    TokenTree::Group(Group::new(delimiter, stream))
+}
@@ -0,0 +1,757 @@
+//! A lossless concrete syntax tree.
+//!
+//! Where [`SnaxItem`] only keeps what's needed to understand markup, the types
+//! in this module keep every token from the input, including punctuation like
+//! `<`, `/`, `>`, and `=`, along with their spans. This is useful for tools
+//! like formatters that need to reproduce or rewrite the original source.
+//!
+//! Printing a tree with [`CstItem::to_token_stream`] produces exactly the
+//! tokens it was parsed from, and [`CstItem::lower`] turns it into the regular
+//! syntax tree.
+//!
+//! [`SnaxItem`]: ../enum.SnaxItem.html
+//! [`CstItem::to_token_stream`]: enum.CstItem.html#method.to_token_stream
+//! [`CstItem::lower`]: enum.CstItem.html#method.lower
+
+use std::{
+    iter::{Cloned, Peekable},
+    slice,
+};
+
+use proc_macro2::{
+    Delimiter,
+    Group,
+    Ident,
+    Punct,
+    Span,
+    TokenStream,
+    TokenTree,
+};
+
+use crate::{
+    OpenToken,
+    ParseError,
+    SnaxAttribute,
+    SnaxItem,
+    close_error,
+    tokenizer::{
+        HtmlToken,
+        TokenizeError,
+        parse_attribute,
+        parse_block,
+        parse_block_head,
+        parse_comma,
+        parse_control_keyword,
+        parse_else,
+        parse_else_if,
+        parse_for_pattern,
+        parse_html_token,
+        parse_if_condition,
+        parse_match_arm_head,
+        parse_optional_block,
+        parse_tag_name,
+    },
+};
+
+/// One complete block in the syntax, mirroring [`SnaxItem`].
+///
+/// [`SnaxItem`]: ../enum.SnaxItem.html
+#[derive(Debug, Clone)]
+pub enum CstItem {
+    Tag(CstTag),
+    SelfClosingTag(CstSelfClosingTag),
+    Fragment(CstFragment),
+    Content(TokenTree),
+    If(CstIf),
+    For(CstFor),
+    Match(CstMatch),
+}
+
+impl CstItem {
+    /// Returns every token in this item, in the order they were parsed.
+    pub fn to_token_stream(&self) -> TokenStream {
+        let mut tokens = Vec::new();
+        self.write_tokens(&mut tokens);
+        tokens.into_iter().collect()
+    }
+
+    /// Converts this item into the regular syntax tree.
+    ///
+    /// The fields of the concrete syntax tree can be changed freely, so this
+    /// parses the item's tokens again and can fail if they're no longer valid.
+    pub fn lower(&self) -> Result<SnaxItem, ParseError> {
+        crate::parse(self.to_token_stream())
+    }
+
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        match self {
+            CstItem::Tag(tag) => {
+                tag.open.write_tokens(tokens);
+
+                for child in &tag.children {
+                    child.write_tokens(tokens);
+                }
+
+                tag.close.write_tokens(tokens);
+            },
+            CstItem::SelfClosingTag(tag) => tag.write_tokens(tokens),
+            CstItem::Fragment(fragment) => {
+                tokens.push(fragment.open_lt.clone().into());
+                tokens.push(fragment.open_gt.clone().into());
+
+                for child in &fragment.children {
+                    child.write_tokens(tokens);
+                }
+
+                tokens.push(fragment.close_lt.clone().into());
+                tokens.push(fragment.close_slash.clone().into());
+                tokens.push(fragment.close_gt.clone().into());
+            },
+            CstItem::Content(content) => tokens.push(content.clone()),
+            CstItem::If(node) => node.write_tokens(tokens),
+            CstItem::For(node) => {
+                tokens.push(node.at.clone().into());
+                tokens.push(node.for_token.clone().into());
+                tokens.extend(node.pattern.iter().cloned());
+                tokens.push(node.in_token.clone().into());
+                tokens.extend(node.iterator.iter().cloned());
+                node.body.write_tokens(tokens);
+            },
+            CstItem::Match(node) => {
+                tokens.push(node.at.clone().into());
+                tokens.push(node.match_token.clone().into());
+                tokens.extend(node.expression.iter().cloned());
+
+                let mut arms = Vec::new();
+                for arm in &node.arms {
+                    arm.write_tokens(&mut arms);
+                }
+
+                let mut group = Group::new(Delimiter::Brace, arms.into_iter().collect());
+                group.set_span(node.span);
+                tokens.push(group.into());
+            },
+        }
+    }
+}
+
+/// A standard tag with its opening tag, children, and closing tag.
+#[derive(Debug, Clone)]
+pub struct CstTag {
+    pub open: CstOpenTag,
+    pub children: Vec<CstItem>,
+    pub close: CstCloseTag,
+}
+
+/// The opening tag of a [`CstTag`], like `<div class="a">`.
+///
+/// [`CstTag`]: struct.CstTag.html
+#[derive(Debug, Clone)]
+pub struct CstOpenTag {
+    pub lt: Punct,
+
+    /// Every token in the tag's name, including `::`, `-`, and any turbofish
+    /// generic arguments.
+    pub name: Vec<TokenTree>,
+    pub attributes: Vec<CstAttribute>,
+    pub gt: Punct,
+}
+
+impl CstOpenTag {
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        tokens.push(self.lt.clone().into());
+        tokens.extend(self.name.iter().cloned());
+
+        for attribute in &self.attributes {
+            attribute.write_tokens(tokens);
+        }
+
+        tokens.push(self.gt.clone().into());
+    }
+}
+
+/// The closing tag of a [`CstTag`], like `</div>`.
+///
+/// [`CstTag`]: struct.CstTag.html
+#[derive(Debug, Clone)]
+pub struct CstCloseTag {
+    pub lt: Punct,
+    pub slash: Punct,
+    pub name: Vec<TokenTree>,
+    pub gt: Punct,
+}
+
+impl CstCloseTag {
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        tokens.push(self.lt.clone().into());
+        tokens.push(self.slash.clone().into());
+        tokens.extend(self.name.iter().cloned());
+        tokens.push(self.gt.clone().into());
+    }
+}
+
+/// A self-closing tag, like `<br />`.
+#[derive(Debug, Clone)]
+pub struct CstSelfClosingTag {
+    pub lt: Punct,
+    pub name: Vec<TokenTree>,
+    pub attributes: Vec<CstAttribute>,
+    pub slash: Punct,
+    pub gt: Punct,
+}
+
+impl CstSelfClosingTag {
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        tokens.push(self.lt.clone().into());
+        tokens.extend(self.name.iter().cloned());
+
+        for attribute in &self.attributes {
+            attribute.write_tokens(tokens);
+        }
+
+        tokens.push(self.slash.clone().into());
+        tokens.push(self.gt.clone().into());
+    }
+}
+
+/// A fragment, from `<>` to `</>`.
+#[derive(Debug, Clone)]
+pub struct CstFragment {
+    pub open_lt: Punct,
+    pub open_gt: Punct,
+    pub children: Vec<CstItem>,
+    pub close_lt: Punct,
+    pub close_slash: Punct,
+    pub close_gt: Punct,
+}
+
+/// A braced list of items, like the body of an `@if`. The braces are rebuilt
+/// from `span` when printing.
+#[derive(Debug, Clone)]
+pub struct CstBlock {
+    pub span: Span,
+    pub children: Vec<CstItem>,
+}
+
+impl CstBlock {
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        let mut children = Vec::new();
+        for child in &self.children {
+            child.write_tokens(&mut children);
+        }
+
+        let mut group = Group::new(Delimiter::Brace, children.into_iter().collect());
+        group.set_span(self.span);
+        tokens.push(group.into());
+    }
+}
+
+/// An `@if`, or the `if` after an `else`, in which case `at` is `None`.
+#[derive(Debug, Clone)]
+pub struct CstIf {
+    pub at: Option<Punct>,
+    pub if_token: Ident,
+    pub condition: Vec<TokenTree>,
+    pub then_branch: CstBlock,
+    pub else_branch: Option<(Ident, CstElse)>,
+}
+
+impl CstIf {
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        tokens.extend(self.at.clone().map(TokenTree::from));
+        tokens.push(self.if_token.clone().into());
+        tokens.extend(self.condition.iter().cloned());
+        self.then_branch.write_tokens(tokens);
+
+        if let Some((else_token, else_branch)) = &self.else_branch {
+            tokens.push(else_token.clone().into());
+
+            match else_branch {
+                CstElse::If(else_if) => else_if.write_tokens(tokens),
+                CstElse::Block(block) => block.write_tokens(tokens),
+            }
+        }
+    }
+}
+
+/// What comes after the `else` of a [`CstIf`].
+///
+/// [`CstIf`]: struct.CstIf.html
+#[derive(Debug, Clone)]
+pub enum CstElse {
+    If(Box<CstIf>),
+    Block(CstBlock),
+}
+
+/// A `@for`, like `@for x in xs { ... }`.
+#[derive(Debug, Clone)]
+pub struct CstFor {
+    pub at: Punct,
+    pub for_token: Ident,
+    pub pattern: Vec<TokenTree>,
+    pub in_token: Ident,
+    pub iterator: Vec<TokenTree>,
+    pub body: CstBlock,
+}
+
+/// A `@match`. The braces around the arms are rebuilt from `span` when
+/// printing.
+#[derive(Debug, Clone)]
+pub struct CstMatch {
+    pub at: Punct,
+    pub match_token: Ident,
+    pub expression: Vec<TokenTree>,
+    pub span: Span,
+    pub arms: Vec<CstMatchArm>,
+}
+
+/// One arm of a [`CstMatch`], including its trailing comma.
+///
+/// [`CstMatch`]: struct.CstMatch.html
+#[derive(Debug, Clone)]
+pub struct CstMatchArm {
+    pub pattern: Vec<TokenTree>,
+    pub guard: Option<(Ident, Vec<TokenTree>)>,
+    pub arrow: (Punct, Punct),
+    pub body: CstArmBody,
+    pub comma: Option<Punct>,
+}
+
+impl CstMatchArm {
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        tokens.extend(self.pattern.iter().cloned());
+
+        if let Some((if_token, guard)) = &self.guard {
+            tokens.push(if_token.clone().into());
+            tokens.extend(guard.iter().cloned());
+        }
+
+        tokens.push(self.arrow.0.clone().into());
+        tokens.push(self.arrow.1.clone().into());
+
+        match &self.body {
+            CstArmBody::Block(block) => block.write_tokens(tokens),
+            CstArmBody::Item(item) => item.write_tokens(tokens),
+        }
+
+        tokens.extend(self.comma.clone().map(TokenTree::from));
+    }
+}
+
+/// The body of a [`CstMatchArm`], which is either a block or a single item.
+///
+/// [`CstMatchArm`]: struct.CstMatchArm.html
+#[derive(Debug, Clone)]
+pub enum CstArmBody {
+    Block(CstBlock),
+    Item(CstItem),
+}
+
+/// An attribute, mirroring [`SnaxAttribute`].
+///
+/// [`SnaxAttribute`]: ../enum.SnaxAttribute.html
+#[derive(Debug, Clone)]
+pub enum CstAttribute {
+    /// `name=value`, where `value` is the tokens of an [`AttributeValue`].
+    ///
+    /// [`AttributeValue`]: ../enum.AttributeValue.html
+    Simple {
+        name: Vec<TokenTree>,
+        eq: Punct,
+        value: Vec<TokenTree>,
+    },
+
+    /// A name without a value.
+    Flag {
+        name: Vec<TokenTree>,
+    },
+
+    /// `name=value`, where `value` is an element or fragment.
+    Markup {
+        name: Vec<TokenTree>,
+        eq: Punct,
+        value: Box<CstItem>,
+    },
+
+    /// `{..value}`, kept as the braced group.
+    Spread(Group),
+
+    /// `{value}`, which is shorthand for `value={value}`, kept as the braced
+    /// group.
+    Shorthand(Group),
+}
+
+impl CstAttribute {
+    fn write_tokens(&self, tokens: &mut Vec<TokenTree>) {
+        match self {
+            CstAttribute::Simple { name, eq, value } => {
+                tokens.extend(name.iter().cloned());
+                tokens.push(eq.clone().into());
+                tokens.extend(value.iter().cloned());
+            },
+            CstAttribute::Flag { name } => tokens.extend(name.iter().cloned()),
+            CstAttribute::Markup { name, eq, value } => {
+                tokens.extend(name.iter().cloned());
+                tokens.push(eq.clone().into());
+                value.write_tokens(tokens);
+            },
+            CstAttribute::Spread(group) | CstAttribute::Shorthand(group) => tokens.push(group.clone().into()),
+        }
+    }
+}
+
+type TokenIter<'a> = Peekable<Cloned<slice::Iter<'a, TokenTree>>>;
+
+/// Runs part of the tokenizer over `tokens`, returning what it parsed along
+/// with how many tokens it consumed.
+fn measure<'a, T, F>(tokens: &'a [TokenTree], parse: F) -> Result<(T, usize), TokenizeError>
+    where F: FnOnce(&mut TokenIter<'a>) -> Result<T, TokenizeError>
+{
+    let mut input = tokens.iter().cloned().peekable();
+    let value = parse(&mut input)?;
+
+    Ok((value, tokens.len() - input.len()))
+}
+
+/// Pulls out a token that the tokenizer has already checked is punctuation.
+fn expect_punct(token: &TokenTree) -> Punct {
+    match token {
+        TokenTree::Punct(punct) => punct.clone(),
+        _ => unreachable!("tokenizer accepted {} as punctuation", token),
+    }
+}
+
+/// Splits the tokens between `<` and the end of an opening or self-closing tag
+/// into its name and attributes.
+fn split_tag(tokens: &[TokenTree]) -> Result<(Vec<TokenTree>, Vec<CstAttribute>), TokenizeError> {
+    let (_, name_length) = measure(tokens, parse_tag_name)?;
+    let mut position = name_length;
+    let mut attributes = Vec::new();
+
+    while position < tokens.len() {
+        let (attribute, length) = measure(&tokens[position..], |input| parse_attribute(input, "an attribute"))?;
+        let raw = &tokens[position..position + length];
+        position += length;
+
+        attributes.push(match attribute {
+            SnaxAttribute::Simple { .. } if length == 1 => match &raw[0] {
+                TokenTree::Group(group) => CstAttribute::Shorthand(group.clone()),
+                token => unreachable!("tokenizer accepted {} as shorthand", token),
+            },
+            SnaxAttribute::Simple { .. } => {
+                let eq_index = find_eq(raw);
+
+                CstAttribute::Simple {
+                    name: raw[..eq_index].to_vec(),
+                    eq: expect_punct(&raw[eq_index]),
+                    value: raw[eq_index + 1..].to_vec(),
+                }
+            },
+            SnaxAttribute::Flag { .. } => CstAttribute::Flag {
+                name: raw.to_vec(),
+            },
+            SnaxAttribute::Markup { .. } => {
+                let eq_index = find_eq(raw);
+                let value = parse_item(&raw[eq_index + 1..], &mut 0)
+                    .map_err(|error| TokenizeError::Markup(Box::new(error)))?;
+
+                CstAttribute::Markup {
+                    name: raw[..eq_index].to_vec(),
+                    eq: expect_punct(&raw[eq_index]),
+                    value: Box::new(value),
+                }
+            },
+            SnaxAttribute::Spread { .. } => match &raw[0] {
+                TokenTree::Group(group) => CstAttribute::Spread(group.clone()),
+                token => unreachable!("tokenizer accepted {} as a spread", token),
+            },
+            SnaxAttribute::Expression { .. } => {
+                unreachable!("the tokenizer doesn't look for markup inside of blocks")
+            },
+        });
+    }
+
+    Ok((tokens[..name_length].to_vec(), attributes))
+}
+
+/// Finds the `=` between an attribute's name and its value. Names are only
+/// identifiers joined by `-`, so the first `=` is always the right one.
+fn find_eq(raw: &[TokenTree]) -> usize {
+    raw.iter()
+        .position(|token| match token {
+            TokenTree::Punct(punct) => punct.as_char() == '=',
+            _ => false,
+        })
+        .expect("attributes with values always contain `=`")
+}
+
+enum CstOpen {
+    Tag(CstOpenTag),
+    Fragment(Punct, Punct),
+}
+
+/// Attempts to parse a `proc_macro2::TokenStream` into a `CstItem`.
+///
+/// This accepts exactly the same input as [`parse`](../fn.parse.html).
+pub fn parse(input_stream: TokenStream) -> Result<CstItem, ParseError> {
+    let tokens: Vec<TokenTree> = input_stream.into_iter().collect();
+    let mut position = 0;
+    let item = parse_item(&tokens, &mut position)?;
+
+    if let Some(token) = tokens.get(position) {
+        return Err(ParseError::UnexpectedToken {
+            token: token.clone(),
+            expected: "the end of the input",
+        });
+    }
+
+    Ok(item)
+}
+
+/// Parses one complete item starting at `position`, moving `position` past it.
+fn parse_item(tokens: &[TokenTree], position: &mut usize) -> Result<CstItem, ParseError> {
+    let mut tag_stack: Vec<(OpenToken, CstOpen, Vec<CstItem>)> = Vec::new();
+
+    loop {
+        if *position == tokens.len() && !tag_stack.is_empty() {
+            let open = tag_stack.iter()
+                .map(|(open_token, _, _)| open_token.name())
+                .collect();
+
+            return Err(ParseError::UnclosedTag { open });
+        }
+
+        let is_control_flow = match tokens.get(*position) {
+            Some(TokenTree::Punct(punct)) => punct.as_char() == '@',
+            _ => false,
+        };
+
+        if is_control_flow {
+            let item = parse_control_flow(tokens, position)?;
+
+            match tag_stack.last_mut() {
+                None => return Ok(item),
+                Some((_, _, parent_children)) => parent_children.push(item),
+            }
+
+            continue;
+        }
+
+        let (token, length) = measure(&tokens[*position..], parse_html_token)?;
+        let raw = &tokens[*position..*position + length];
+        *position += length;
+
+        let item = match token {
+            HtmlToken::OpenTag(opening_tag) => {
+                let (name, attributes) = split_tag(&raw[1..length - 1])?;
+
+                let open = CstOpenTag {
+                    lt: expect_punct(&raw[0]),
+                    name,
+                    attributes,
+                    gt: expect_punct(&raw[length - 1]),
+                };
+
+                tag_stack.push((OpenToken::Tag(opening_tag), CstOpen::Tag(open), Vec::new()));
+                continue;
+            },
+            HtmlToken::OpenFragment(span) => {
+                let open = CstOpen::Fragment(expect_punct(&raw[0]), expect_punct(&raw[1]));
+
+                tag_stack.push((OpenToken::Fragment(span), open, Vec::new()));
+                continue;
+            },
+            closing @ HtmlToken::CloseTag(_) | closing @ HtmlToken::CloseFragment(_) => {
+                let top_matches = match tag_stack.last() {
+                    Some((open_token, _, _)) => open_token.is_closed_by(&closing),
+                    None => false,
+                };
+
+                if !top_matches {
+                    let opening = tag_stack.last().map(|(open_token, _, _)| open_token);
+                    return Err(close_error(opening, closing));
+                }
+
+                let (_, open, children) = tag_stack.pop()
+                    .expect("tag stack was checked to be non-empty");
+
+                match open {
+                    CstOpen::Tag(open) => CstItem::Tag(CstTag {
+                        open,
+                        children,
+                        close: CstCloseTag {
+                            lt: expect_punct(&raw[0]),
+                            slash: expect_punct(&raw[1]),
+                            name: raw[2..length - 1].to_vec(),
+                            gt: expect_punct(&raw[length - 1]),
+                        },
+                    }),
+                    CstOpen::Fragment(open_lt, open_gt) => CstItem::Fragment(CstFragment {
+                        open_lt,
+                        open_gt,
+                        children,
+                        close_lt: expect_punct(&raw[0]),
+                        close_slash: expect_punct(&raw[1]),
+                        close_gt: expect_punct(&raw[2]),
+                    }),
+                }
+            },
+            HtmlToken::SelfClosingTag(_) => {
+                let (name, attributes) = split_tag(&raw[1..length - 2])?;
+
+                CstItem::SelfClosingTag(CstSelfClosingTag {
+                    lt: expect_punct(&raw[0]),
+                    name,
+                    attributes,
+                    slash: expect_punct(&raw[length - 2]),
+                    gt: expect_punct(&raw[length - 1]),
+                })
+            },
+            HtmlToken::Textish(textish) => CstItem::Content(textish.content),
+        };
+
+        match tag_stack.last_mut() {
+            None => return Ok(item),
+            Some((_, _, parent_children)) => parent_children.push(item),
+        }
+    }
+}
+
+/// Parses the contents of a block as a list of items.
+fn parse_children(block: Group) -> Result<CstBlock, ParseError> {
+    let tokens: Vec<TokenTree> = block.stream().into_iter().collect();
+    let mut position = 0;
+    let mut children = Vec::new();
+
+    while position < tokens.len() {
+        children.push(parse_item(&tokens, &mut position)?);
+    }
+
+    Ok(CstBlock {
+        span: block.span(),
+        children,
+    })
+}
+
+/// Parses a control-flow item starting at the `@` at `position`.
+fn parse_control_flow(tokens: &[TokenTree], position: &mut usize) -> Result<CstItem, ParseError> {
+    let ((at, keyword), length) = measure(&tokens[*position..], parse_control_keyword)?;
+    *position += length;
+
+    if keyword == "if" {
+        return Ok(CstItem::If(parse_if(tokens, position, Some(at), keyword)?));
+    }
+
+    if keyword == "for" {
+        let ((pattern, in_token), length) = measure(&tokens[*position..], parse_for_pattern)?;
+        *position += length;
+
+        let ((iterator, body), length) = measure(&tokens[*position..], |input| {
+            parse_block_head(input, "an iterator after `in`", "a block for the body of `@for`")
+        })?;
+        *position += length;
+
+        return Ok(CstItem::For(CstFor {
+            at,
+            for_token: keyword,
+            pattern,
+            in_token,
+            iterator,
+            body: parse_children(body)?,
+        }));
+    }
+
+    let ((expression, arms), length) = measure(&tokens[*position..], |input| {
+        parse_block_head(input, "an expression after `@match`", "a block of arms for `@match`")
+    })?;
+    *position += length;
+
+    Ok(CstItem::Match(CstMatch {
+        at,
+        match_token: keyword,
+        expression,
+        span: arms.span(),
+        arms: parse_match_arms(arms)?,
+    }))
+}
+
+/// Parses the rest of an `@if` or `else if`, starting after `if`.
+fn parse_if(tokens: &[TokenTree], position: &mut usize, at: Option<Punct>, if_token: Ident) -> Result<CstIf, ParseError> {
+    let ((condition, then_block), length) = measure(&tokens[*position..], parse_if_condition)?;
+    *position += length;
+
+    let then_branch = parse_children(then_block)?;
+
+    let (else_token, length) = measure(&tokens[*position..], |input| Ok(parse_else(input)))?;
+    *position += length;
+
+    let else_branch = match else_token {
+        None => None,
+        Some(else_token) => {
+            let (else_if, length) = measure(&tokens[*position..], |input| Ok(parse_else_if(input)))?;
+            *position += length;
+
+            let branch = match else_if {
+                Some(if_token) => CstElse::If(Box::new(parse_if(tokens, position, None, if_token)?)),
+                None => {
+                    let (block, length) = measure(&tokens[*position..], |input| {
+                        parse_block(input, "`if` or a block after `else`")
+                    })?;
+                    *position += length;
+
+                    CstElse::Block(parse_children(block)?)
+                },
+            };
+
+            Some((else_token, branch))
+        },
+    };
+
+    Ok(CstIf {
+        at,
+        if_token,
+        condition,
+        then_branch,
+        else_branch,
+    })
+}
+
+/// Parses the arms inside the block of a `@match`.
+fn parse_match_arms(block: Group) -> Result<Vec<CstMatchArm>, ParseError> {
+    let tokens: Vec<TokenTree> = block.stream().into_iter().collect();
+    let mut position = 0;
+    let mut arms = Vec::new();
+
+    while position < tokens.len() {
+        let (head, length) = measure(&tokens[position..], parse_match_arm_head)?;
+        position += length;
+
+        let (body_block, length) = measure(&tokens[position..], |input| Ok(parse_optional_block(input)))?;
+        position += length;
+
+        let body = match body_block {
+            Some(block) => CstArmBody::Block(parse_children(block)?),
+            None => CstArmBody::Item(parse_item(&tokens, &mut position)?),
+        };
+
+        let (comma, length) = measure(&tokens[position..], |input| Ok(parse_comma(input)))?;
+        position += length;
+
+        if let (None, CstArmBody::Item(_), Some(token)) = (&comma, &body, tokens.get(position)) {
+            return Err(ParseError::UnexpectedToken {
+                token: token.clone(),
+                expected: "`,` after the match arm",
+            });
+        }
+
+        arms.push(CstMatchArm {
+            pattern: head.pattern,
+            guard: head.guard,
+            arrow: head.arrow,
+            body,
+            comma,
+        });
+    }
+
+    Ok(arms)
+}
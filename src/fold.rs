@@ -0,0 +1,268 @@
+//! Traversal that takes ownership of a syntax tree and rebuilds it, in the
+//! style of `syn::fold`.
+//!
+//! This works the same way as [`visit`](../visit/index.html), but each method
+//! takes a node by value and returns its replacement. The default
+//! implementations rebuild each node from its folded children.
+//!
+//! ```
+//! use snax::{SnaxItem, fold::{self, Fold}};
+//!
+//! /// Replaces every fragment with its first child.
+//! struct Unwrap;
+//!
+//! impl Fold for Unwrap {
+//!     fn fold_item(&mut self, item: SnaxItem) -> SnaxItem {
+//!         match item {
+//!             SnaxItem::Fragment(mut fragment) if !fragment.children.is_empty() => {
+//!                 self.fold_item(fragment.children.remove(0))
+//!             },
+//!             item => fold::fold_item(self, item),
+//!         }
+//!     }
+//! }
+//! ```
+
+use proc_macro2::TokenTree;
+
+use crate::{
+    SnaxAttribute,
+    SnaxExpression,
+    SnaxExpressionPart,
+    SnaxFor,
+    SnaxFragment,
+    SnaxIf,
+    SnaxItem,
+    SnaxMatch,
+    SnaxMatchArm,
+    SnaxName,
+    SnaxPath,
+    SnaxSelfClosingTag,
+    SnaxTag,
+};
+
+/// Transforms each node of a syntax tree by value.
+pub trait Fold {
+    fn fold_item(&mut self, node: SnaxItem) -> SnaxItem {
+        fold_item(self, node)
+    }
+
+    fn fold_tag(&mut self, node: SnaxTag) -> SnaxTag {
+        fold_tag(self, node)
+    }
+
+    fn fold_self_closing_tag(&mut self, node: SnaxSelfClosingTag) -> SnaxSelfClosingTag {
+        fold_self_closing_tag(self, node)
+    }
+
+    fn fold_fragment(&mut self, node: SnaxFragment) -> SnaxFragment {
+        fold_fragment(self, node)
+    }
+
+    fn fold_content(&mut self, node: TokenTree) -> TokenTree {
+        fold_content(self, node)
+    }
+
+    /// Folds a block of content or an attribute value with markup embedded in
+    /// it.
+    fn fold_expression(&mut self, node: SnaxExpression) -> SnaxExpression {
+        fold_expression(self, node)
+    }
+
+    fn fold_if(&mut self, node: SnaxIf) -> SnaxIf {
+        fold_if(self, node)
+    }
+
+    fn fold_for(&mut self, node: SnaxFor) -> SnaxFor {
+        fold_for(self, node)
+    }
+
+    fn fold_match(&mut self, node: SnaxMatch) -> SnaxMatch {
+        fold_match(self, node)
+    }
+
+    fn fold_match_arm(&mut self, node: SnaxMatchArm) -> SnaxMatchArm {
+        fold_match_arm(self, node)
+    }
+
+    fn fold_attribute(&mut self, node: SnaxAttribute) -> SnaxAttribute {
+        fold_attribute(self, node)
+    }
+
+    /// Folds the name of a tag.
+    fn fold_path(&mut self, node: SnaxPath) -> SnaxPath {
+        fold_path(self, node)
+    }
+
+    /// Folds the name of an attribute or a segment of a tag's name.
+    fn fold_name(&mut self, node: SnaxName) -> SnaxName {
+        fold_name(self, node)
+    }
+}
+
+pub fn fold_item<F>(folder: &mut F, node: SnaxItem) -> SnaxItem
+    where F: Fold + ?Sized
+{
+    match node {
+        SnaxItem::Tag(tag) => SnaxItem::Tag(folder.fold_tag(tag)),
+        SnaxItem::SelfClosingTag(tag) => SnaxItem::SelfClosingTag(folder.fold_self_closing_tag(tag)),
+        SnaxItem::Fragment(fragment) => SnaxItem::Fragment(folder.fold_fragment(fragment)),
+        SnaxItem::Content(content) => SnaxItem::Content(folder.fold_content(content)),
+        SnaxItem::Expression(expression) => SnaxItem::Expression(folder.fold_expression(expression)),
+        SnaxItem::If(node) => SnaxItem::If(folder.fold_if(node)),
+        SnaxItem::For(node) => SnaxItem::For(folder.fold_for(node)),
+        SnaxItem::Match(node) => SnaxItem::Match(folder.fold_match(node)),
+    }
+}
+
+pub fn fold_tag<F>(folder: &mut F, node: SnaxTag) -> SnaxTag
+    where F: Fold + ?Sized
+{
+    SnaxTag {
+        name: folder.fold_path(node.name),
+        generics: node.generics,
+        attributes: node.attributes.into_iter()
+            .map(|attribute| folder.fold_attribute(attribute))
+            .collect(),
+        children: fold_items(folder, node.children),
+        open_span: node.open_span,
+        close_span: node.close_span,
+    }
+}
+
+pub fn fold_self_closing_tag<F>(folder: &mut F, node: SnaxSelfClosingTag) -> SnaxSelfClosingTag
+    where F: Fold + ?Sized
+{
+    SnaxSelfClosingTag {
+        name: folder.fold_path(node.name),
+        generics: node.generics,
+        attributes: node.attributes.into_iter()
+            .map(|attribute| folder.fold_attribute(attribute))
+            .collect(),
+        span: node.span,
+    }
+}
+
+pub fn fold_fragment<F>(folder: &mut F, node: SnaxFragment) -> SnaxFragment
+    where F: Fold + ?Sized
+{
+    SnaxFragment {
+        children: fold_items(folder, node.children),
+        open_span: node.open_span,
+        close_span: node.close_span,
+    }
+}
+
+pub fn fold_content<F>(_folder: &mut F, node: TokenTree) -> TokenTree
+    where F: Fold + ?Sized
+{
+    node
+}
+
+pub fn fold_expression<F>(folder: &mut F, node: SnaxExpression) -> SnaxExpression
+    where F: Fold + ?Sized
+{
+    SnaxExpression {
+        delimiter: node.delimiter,
+        parts: node.parts.into_iter()
+            .map(|part| match part {
+                token @ SnaxExpressionPart::Token(_) => token,
+                SnaxExpressionPart::Group(group) => SnaxExpressionPart::Group(folder.fold_expression(group)),
+                SnaxExpressionPart::Item(item) => SnaxExpressionPart::Item(folder.fold_item(item)),
+            })
+            .collect(),
+        span: node.span,
+    }
+}
+
+pub fn fold_if<F>(folder: &mut F, node: SnaxIf) -> SnaxIf
+    where F: Fold + ?Sized
+{
+    SnaxIf {
+        condition: node.condition,
+        then_branch: fold_items(folder, node.then_branch),
+        else_branch: node.else_branch.map(|branch| fold_items(folder, branch)),
+        span: node.span,
+    }
+}
+
+pub fn fold_for<F>(folder: &mut F, node: SnaxFor) -> SnaxFor
+    where F: Fold + ?Sized
+{
+    SnaxFor {
+        pattern: node.pattern,
+        iterator: node.iterator,
+        body: fold_items(folder, node.body),
+        span: node.span,
+    }
+}
+
+pub fn fold_match<F>(folder: &mut F, node: SnaxMatch) -> SnaxMatch
+    where F: Fold + ?Sized
+{
+    SnaxMatch {
+        expression: node.expression,
+        arms: node.arms.into_iter()
+            .map(|arm| folder.fold_match_arm(arm))
+            .collect(),
+        span: node.span,
+    }
+}
+
+pub fn fold_match_arm<F>(folder: &mut F, node: SnaxMatchArm) -> SnaxMatchArm
+    where F: Fold + ?Sized
+{
+    SnaxMatchArm {
+        pattern: node.pattern,
+        guard: node.guard,
+        body: fold_items(folder, node.body),
+    }
+}
+
+fn fold_items<F>(folder: &mut F, items: Vec<SnaxItem>) -> Vec<SnaxItem>
+    where F: Fold + ?Sized
+{
+    items.into_iter()
+        .map(|item| folder.fold_item(item))
+        .collect()
+}
+
+pub fn fold_attribute<F>(folder: &mut F, node: SnaxAttribute) -> SnaxAttribute
+    where F: Fold + ?Sized
+{
+    match node {
+        SnaxAttribute::Simple { name, value } => SnaxAttribute::Simple {
+            name: folder.fold_name(name),
+            value,
+        },
+        SnaxAttribute::Flag { name } => SnaxAttribute::Flag {
+            name: folder.fold_name(name),
+        },
+        spread @ SnaxAttribute::Spread { .. } => spread,
+        SnaxAttribute::Markup { name, value } => SnaxAttribute::Markup {
+            name: folder.fold_name(name),
+            value: Box::new(folder.fold_item(*value)),
+        },
+        SnaxAttribute::Expression { name, value } => SnaxAttribute::Expression {
+            name: folder.fold_name(name),
+            value: folder.fold_expression(value),
+        },
+    }
+}
+
+pub fn fold_path<F>(folder: &mut F, node: SnaxPath) -> SnaxPath
+    where F: Fold + ?Sized
+{
+    SnaxPath {
+        first: folder.fold_name(node.first),
+        rest: node.rest.into_iter()
+            .map(|segment| folder.fold_name(segment))
+            .collect(),
+    }
+}
+
+pub fn fold_name<F>(_folder: &mut F, node: SnaxName) -> SnaxName
+    where F: Fold + ?Sized
+{
+    node
+}
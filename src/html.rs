@@ -0,0 +1,221 @@
+//! Rendering of templates that don't contain any Rust expressions.
+
+use std::fmt;
+
+use proc_macro2::{Span, TokenTree};
+
+use crate::{
+    literal::literal_text,
+    AttributeValue,
+    SnaxAttribute,
+    SnaxItem,
+    SnaxPath,
+};
+
+/// HTML elements that never have children, which are written without a
+/// closing tag.
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input",
+    "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Renders a tree to an HTML string at macro time.
+///
+/// This only works when every attribute value and every piece of content is
+/// a literal. String literals are decoded and escaped, fragments are replaced
+/// by their children, and self-closing tags are written as `<br>` for void
+/// elements and as `<div></div>` for everything else.
+///
+/// ```
+/// use quote::quote;
+///
+/// let item = snax::parse(quote!(
+///     <p class="note">"Fish & chips"<br /></p>
+/// )).unwrap();
+///
+/// assert_eq!(
+///     snax::html::render_static(&item).unwrap(),
+///     r#"<p class="note">Fish &amp; chips<br></p>"#,
+/// );
+/// ```
+///
+/// If any part of the tree needs to be evaluated at runtime, the first such
+/// part is returned as a [`Dynamic`] error.
+///
+/// [`Dynamic`]: enum.Dynamic.html
+pub fn render_static(item: &SnaxItem) -> Result<String, Dynamic> {
+    let mut output = String::new();
+    render_item(item, &mut output)?;
+    Ok(output)
+}
+
+/// The part of a tree that stopped [`render_static`] from rendering it.
+///
+/// [`render_static`]: fn.render_static.html
+#[derive(Debug, Clone)]
+pub enum Dynamic {
+    /// Content or an attribute value that isn't a string, character, or
+    /// numeric literal, like a `{}` block or markup.
+    Value {
+        span: Span,
+    },
+
+    /// An attribute spread like `{..props}`.
+    Spread {
+        span: Span,
+    },
+
+    /// A component tag, which is any tag whose name is a path like
+    /// `ui::Button` or that has generic arguments.
+    Component {
+        span: Span,
+    },
+
+    /// An `@if`, `@for`, or `@match`.
+    ControlFlow {
+        span: Span,
+    },
+}
+
+impl Dynamic {
+    /// Returns the span of the dynamic part of the tree.
+    pub fn span(&self) -> Span {
+        match self {
+            Dynamic::Value { span } => *span,
+            Dynamic::Spread { span } => *span,
+            Dynamic::Component { span } => *span,
+            Dynamic::ControlFlow { span } => *span,
+        }
+    }
+}
+
+impl fmt::Display for Dynamic {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Dynamic::Value { .. } => write!(formatter, "only literals can be rendered statically"),
+            Dynamic::Spread { .. } => write!(formatter, "attribute spreads can't be rendered statically"),
+            Dynamic::Component { .. } => write!(formatter, "components can't be rendered statically"),
+            Dynamic::ControlFlow { .. } => write!(formatter, "control flow can't be rendered statically"),
+        }
+    }
+}
+
+impl std::error::Error for Dynamic {}
+
+fn render_item(item: &SnaxItem, output: &mut String) -> Result<(), Dynamic> {
+    match item {
+        SnaxItem::Tag(tag) => {
+            let name = element_name(&tag.name, tag.generics.is_some(), tag.open_span)?;
+
+            render_open_tag(&name, &tag.attributes, output)?;
+
+            for child in &tag.children {
+                render_item(child, output)?;
+            }
+
+            render_close_tag(&name, output);
+        },
+        SnaxItem::SelfClosingTag(tag) => {
+            let name = element_name(&tag.name, tag.generics.is_some(), tag.span)?;
+
+            render_open_tag(&name, &tag.attributes, output)?;
+
+            if !VOID_ELEMENTS.contains(&name.as_str()) {
+                render_close_tag(&name, output);
+            }
+        },
+        SnaxItem::Fragment(fragment) => {
+            for child in &fragment.children {
+                render_item(child, output)?;
+            }
+        },
+        SnaxItem::Content(content) => {
+            escape(&static_value(content)?, false, output);
+        },
+        SnaxItem::Expression(expression) => {
+            return Err(Dynamic::Value { span: expression.span() });
+        },
+        SnaxItem::If(_) | SnaxItem::For(_) | SnaxItem::Match(_) => {
+            return Err(Dynamic::ControlFlow { span: item.span() });
+        },
+    }
+
+    Ok(())
+}
+
+fn element_name(name: &SnaxPath, has_generics: bool, span: Span) -> Result<String, Dynamic> {
+    match name.as_name() {
+        Some(name) if !has_generics => Ok(name.to_string()),
+        _ => Err(Dynamic::Component { span }),
+    }
+}
+
+fn render_open_tag(name: &str, attributes: &[SnaxAttribute], output: &mut String) -> Result<(), Dynamic> {
+    output.push('<');
+    output.push_str(name);
+
+    for attribute in attributes {
+        match attribute {
+            SnaxAttribute::Simple { name, value: AttributeValue::Bool { value, .. } } => {
+                if *value {
+                    output.push(' ');
+                    output.push_str(&name.to_string());
+                }
+            },
+            SnaxAttribute::Simple { name, value } => {
+                let value = value.literal_text()
+                    .ok_or_else(|| Dynamic::Value { span: value.span() })?;
+
+                output.push(' ');
+                output.push_str(&name.to_string());
+                output.push_str("=\"");
+                escape(&value, true, output);
+                output.push('"');
+            },
+            SnaxAttribute::Flag { name } => {
+                output.push(' ');
+                output.push_str(&name.to_string());
+            },
+            SnaxAttribute::Spread { span, .. } => {
+                return Err(Dynamic::Spread { span: *span });
+            },
+            SnaxAttribute::Markup { value, .. } => {
+                return Err(Dynamic::Value { span: value.span() });
+            },
+            SnaxAttribute::Expression { value, .. } => {
+                return Err(Dynamic::Value { span: value.span() });
+            },
+        }
+    }
+
+    output.push('>');
+    Ok(())
+}
+
+fn render_close_tag(name: &str, output: &mut String) {
+    output.push_str("</");
+    output.push_str(name);
+    output.push('>');
+}
+
+fn static_value(value: &TokenTree) -> Result<String, Dynamic> {
+    match value {
+        TokenTree::Literal(literal) => literal_text(literal)
+            .ok_or_else(|| Dynamic::Value { span: literal.span() }),
+        other => Err(Dynamic::Value { span: other.span() }),
+    }
+}
+
+/// Escapes text for use in HTML. Quotes are only escaped inside of attribute
+/// values, which are always double-quoted.
+pub(crate) fn escape(text: &str, in_attribute: bool, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' if in_attribute => output.push_str("&quot;"),
+            c => output.push(c),
+        }
+    }
+}
@@ -0,0 +1,585 @@
+//! An intermediate representation that splits a tree into a static skeleton
+//! and a list of dynamic holes, like the templates used by lit-html and Solid.
+//!
+//! A framework would typically render the skeleton to HTML once, clone it for
+//! each instance, and then walk to each hole using its [`path`] to fill it in.
+//!
+//! ```
+//! use quote::quote;
+//! use snax::ir::{self, HoleKind, Node};
+//!
+//! let template = ir::lower(&snax::parse(quote!(
+//!     <div class="card">
+//!         <h1>"Title"</h1>
+//!         <p>{ body }</p>
+//!     </div>
+//! )).unwrap());
+//!
+//! assert_eq!(template.holes.len(), 1);
+//! assert_eq!(template.holes[0].path, vec![0, 1, 0]);
+//! assert!(matches!(template.holes[0].kind, HoleKind::Content { .. }));
+//!
+//! assert_eq!(template.statics.len(), 1);
+//! assert_eq!(template.node_to_html(&Node::Static(0)), "<h1>Title</h1>");
+//! assert_eq!(template.to_html(), r#"<div class="card"><h1>Title</h1><p><!----></p></div>"#);
+//! ```
+//!
+//! [`path`]: struct.Hole.html#structfield.path
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+
+use crate::{
+    html::{escape, VOID_ELEMENTS},
+    literal::literal_text,
+    value_expression,
+    AttributeValue,
+    SnaxAttribute,
+    SnaxExpression,
+    SnaxItem,
+    SnaxPath,
+};
+
+/// Lowers a tree into a [`Template`].
+///
+/// Fragments are flattened into their parents, adjacent pieces of literal text
+/// are merged into a single [`Node::Text`], and empty text is dropped. Holes are numbered in
+/// document order, so the same input always produces the same indices.
+///
+/// [`Template`]: struct.Template.html
+/// [`Node::Text`]: enum.Node.html#variant.Text
+pub fn lower(item: &SnaxItem) -> Template {
+    lower_items(std::slice::from_ref(item))
+}
+
+fn lower_items(items: &[SnaxItem]) -> Template {
+    let mut template = Template {
+        nodes: Vec::new(),
+        holes: Vec::new(),
+        statics: Vec::new(),
+    };
+
+    for item in items {
+        lower_item(item, &mut template.nodes, &mut Vec::new(), &mut template.holes);
+    }
+
+    for node in &mut template.nodes {
+        hoist(node, &mut template.statics);
+    }
+
+    template
+}
+
+/// A tree split into its static skeleton and its dynamic holes.
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// The top-level nodes of the skeleton.
+    pub nodes: Vec<Node>,
+
+    /// Every hole in the skeleton, indexed by the numbers stored in
+    /// [`Node::Hole`] and [`Attribute`].
+    ///
+    /// [`Node::Hole`]: enum.Node.html#variant.Hole
+    /// [`Attribute`]: enum.Attribute.html
+    pub holes: Vec<Hole>,
+
+    /// Elements that don't contain any holes, hoisted out of the skeleton and
+    /// referred to by [`Node::Static`]. Only the outermost element of each
+    /// static subtree is hoisted.
+    ///
+    /// [`Node::Static`]: enum.Node.html#variant.Static
+    pub statics: Vec<Element>,
+}
+
+impl Template {
+    /// Renders the skeleton to HTML. Holes in place of nodes are written
+    /// as `<!---->` comments, which can be used as markers when filling them
+    /// in, and dynamic attributes are left out.
+    pub fn to_html(&self) -> String {
+        let mut output = String::new();
+
+        for node in &self.nodes {
+            self.write_node(node, &mut output);
+        }
+
+        output
+    }
+
+    /// Renders a single node of the skeleton to HTML, like
+    /// [`to_html`](#method.to_html). This can be used to render one of the
+    /// hoisted static elements with `Node::Static(index)`.
+    pub fn node_to_html(&self, node: &Node) -> String {
+        let mut output = String::new();
+        self.write_node(node, &mut output);
+        output
+    }
+
+    fn write_node(&self, node: &Node, output: &mut String) {
+        match node {
+            Node::Element(element) => self.write_element(element, output),
+            Node::Text(text) => escape(text, false, output),
+            Node::Hole(_) => output.push_str("<!---->"),
+            Node::Static(index) => self.write_element(&self.statics[*index], output),
+        }
+    }
+
+    fn write_element(&self, element: &Element, output: &mut String) {
+        output.push('<');
+        output.push_str(&element.name);
+
+        for attribute in &element.attributes {
+            if let Attribute::Static { name, value } = attribute {
+                output.push(' ');
+                output.push_str(name);
+
+                if let Some(value) = value {
+                    output.push_str("=\"");
+                    escape(value, true, output);
+                    output.push('"');
+                }
+            }
+        }
+
+        output.push('>');
+
+        if VOID_ELEMENTS.contains(&element.name.as_str()) && element.children.is_empty() {
+            return;
+        }
+
+        for child in &element.children {
+            self.write_node(child, output);
+        }
+
+        output.push_str("</");
+        output.push_str(&element.name);
+        output.push('>');
+    }
+}
+
+/// A node in the static skeleton of a [`Template`].
+///
+/// [`Template`]: struct.Template.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    /// An HTML element, which may contain holes.
+    Element(Element),
+
+    /// Literal text, already decoded from its Rust string literal.
+    Text(String),
+
+    /// A content, component, or control-flow hole, holding its index in
+    /// `Template::holes`.
+    Hole(usize),
+
+    /// A hoisted static element, holding its index in `Template::statics`.
+    Static(usize),
+}
+
+/// An HTML element in the skeleton.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+/// An attribute of an [`Element`].
+///
+/// [`Element`]: struct.Element.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum Attribute {
+    /// An attribute with a literal value, or no value for flags.
+    Static {
+        name: String,
+        value: Option<String>,
+    },
+
+    /// An attribute whose value is a hole.
+    Dynamic {
+        name: String,
+        hole: usize,
+    },
+
+    /// An attribute spread, which is a hole.
+    Spread {
+        hole: usize,
+    },
+}
+
+/// A dynamic part of a [`Template`].
+///
+/// [`Template`]: struct.Template.html
+#[derive(Debug, Clone)]
+pub struct Hole {
+    /// The position of the hole in the skeleton, as indices into each level
+    /// of children starting from `Template::nodes`. For content, components,
+    /// and control flow this points at the [`Node::Hole`], and for attributes
+    /// it points at the element they belong to.
+    ///
+    /// [`Node::Hole`]: enum.Node.html#variant.Hole
+    pub path: Vec<usize>,
+    pub kind: HoleKind,
+    pub span: Span,
+}
+
+/// The kinds of [`Hole`].
+///
+/// [`Hole`]: struct.Hole.html
+#[derive(Debug, Clone)]
+pub enum HoleKind {
+    /// Content that's evaluated at runtime, like `{ name }`. `value` is the
+    /// expression without its surrounding braces.
+    Content {
+        value: TokenStream,
+    },
+
+    /// An attribute value that's evaluated at runtime, like `class={ class }`.
+    Attribute {
+        name: String,
+        value: TokenStream,
+    },
+
+    /// An attribute spread like `{..props}`.
+    Spread {
+        value: TokenStream,
+    },
+
+    /// Content or an attribute value with markup embedded in it, from
+    /// [`parse_embedded`]. `name` is the name of the attribute, or `None` for
+    /// content. Each embedded item is lowered into its own template, in the
+    /// same order as [`SnaxExpression::items`].
+    ///
+    /// [`parse_embedded`]: ../fn.parse_embedded.html
+    /// [`SnaxExpression::items`]: ../struct.SnaxExpression.html#method.items
+    Expression {
+        name: Option<String>,
+        expression: SnaxExpression,
+        templates: Vec<Template>,
+    },
+
+    /// An attribute whose value is an element or fragment, like
+    /// `header=<Header />`, lowered into its own template.
+    Markup {
+        name: String,
+        template: Template,
+    },
+
+    /// A component tag, which is any tag whose name is a path like
+    /// `ui::Button` or that has generic arguments. Its children are lowered
+    /// into their own template, with their own holes.
+    Component {
+        name: SnaxPath,
+        generics: Option<TokenStream>,
+        attributes: Vec<SnaxAttribute>,
+        children: Template,
+    },
+
+    /// An `@if`, with each branch lowered into its own template.
+    If {
+        condition: TokenStream,
+        then_branch: Template,
+        else_branch: Option<Template>,
+    },
+
+    /// A `@for`, with its body lowered into its own template.
+    For {
+        pattern: TokenStream,
+        iterator: TokenStream,
+        body: Template,
+    },
+
+    /// A `@match`, with each arm lowered into its own template.
+    Match {
+        expression: TokenStream,
+        arms: Vec<MatchArm>,
+    },
+}
+
+/// One arm of a [`HoleKind::Match`].
+///
+/// [`HoleKind::Match`]: enum.HoleKind.html#variant.Match
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: TokenStream,
+    pub guard: Option<TokenStream>,
+    pub body: Template,
+}
+
+fn lower_item(item: &SnaxItem, nodes: &mut Vec<Node>, path: &mut Vec<usize>, holes: &mut Vec<Hole>) {
+    match item {
+        SnaxItem::Tag(tag) => {
+            if is_component(&tag.name, &tag.generics) {
+                let component = lower_component(&tag.name, &tag.generics, &tag.attributes, &tag.children);
+                push_hole(component, tag.span(), nodes, path, holes);
+                return;
+            }
+
+            path.push(nodes.len());
+
+            let attributes = lower_attributes(&tag.attributes, path, holes);
+            let mut children = Vec::new();
+
+            for child in &tag.children {
+                lower_item(child, &mut children, path, holes);
+            }
+
+            path.pop();
+
+            nodes.push(Node::Element(Element {
+                name: tag.name.to_string(),
+                attributes,
+                children,
+            }));
+        },
+        SnaxItem::SelfClosingTag(tag) => {
+            if is_component(&tag.name, &tag.generics) {
+                let component = lower_component(&tag.name, &tag.generics, &tag.attributes, &[]);
+                push_hole(component, tag.span, nodes, path, holes);
+                return;
+            }
+
+            path.push(nodes.len());
+            let attributes = lower_attributes(&tag.attributes, path, holes);
+            path.pop();
+
+            nodes.push(Node::Element(Element {
+                name: tag.name.to_string(),
+                attributes,
+                children: Vec::new(),
+            }));
+        },
+        SnaxItem::Fragment(fragment) => {
+            for child in &fragment.children {
+                lower_item(child, nodes, path, holes);
+            }
+        },
+        SnaxItem::Content(content) => {
+            if let Some(text) = static_text(content) {
+                // Empty text doesn't make a DOM node, so it would throw off
+                // the paths of any holes after it.
+                if text.is_empty() {
+                    return;
+                }
+
+                if let Some(Node::Text(previous)) = nodes.last_mut() {
+                    previous.push_str(&text);
+                } else {
+                    nodes.push(Node::Text(text));
+                }
+
+                return;
+            }
+
+            let kind = HoleKind::Content {
+                value: value_expression(content),
+            };
+
+            push_hole(kind, content.span(), nodes, path, holes);
+        },
+        SnaxItem::Expression(expression) => {
+            push_hole(lower_expression(None, expression), expression.span, nodes, path, holes);
+        },
+        SnaxItem::If(node) => {
+            let kind = HoleKind::If {
+                condition: node.condition.clone(),
+                then_branch: lower_items(&node.then_branch),
+                else_branch: node.else_branch.as_ref().map(|branch| lower_items(branch)),
+            };
+
+            push_hole(kind, node.span, nodes, path, holes);
+        },
+        SnaxItem::For(node) => {
+            let kind = HoleKind::For {
+                pattern: node.pattern.clone(),
+                iterator: node.iterator.clone(),
+                body: lower_items(&node.body),
+            };
+
+            push_hole(kind, node.span, nodes, path, holes);
+        },
+        SnaxItem::Match(node) => {
+            let kind = HoleKind::Match {
+                expression: node.expression.clone(),
+                arms: node.arms.iter()
+                    .map(|arm| MatchArm {
+                        pattern: arm.pattern.clone(),
+                        guard: arm.guard.clone(),
+                        body: lower_items(&arm.body),
+                    })
+                    .collect(),
+            };
+
+            push_hole(kind, node.span, nodes, path, holes);
+        },
+    }
+}
+
+fn is_component(name: &SnaxPath, generics: &Option<TokenStream>) -> bool {
+    name.as_name().is_none() || generics.is_some()
+}
+
+fn lower_component(
+    name: &SnaxPath,
+    generics: &Option<TokenStream>,
+    attributes: &[SnaxAttribute],
+    children: &[SnaxItem],
+) -> HoleKind {
+    HoleKind::Component {
+        name: name.clone(),
+        generics: generics.clone(),
+        attributes: attributes.to_vec(),
+        children: lower_items(children),
+    }
+}
+
+fn lower_expression(name: Option<String>, expression: &SnaxExpression) -> HoleKind {
+    HoleKind::Expression {
+        name,
+        expression: expression.clone(),
+        templates: expression.items()
+            .into_iter()
+            .map(lower)
+            .collect(),
+    }
+}
+
+/// Adds a hole in place of a node.
+fn push_hole(kind: HoleKind, span: Span, nodes: &mut Vec<Node>, path: &[usize], holes: &mut Vec<Hole>) {
+    let mut hole_path = path.to_vec();
+    hole_path.push(nodes.len());
+
+    nodes.push(Node::Hole(holes.len()));
+    holes.push(Hole {
+        path: hole_path,
+        kind,
+        span,
+    });
+}
+
+/// Lowers the attributes of the element at `path`.
+fn lower_attributes(attributes: &[SnaxAttribute], path: &[usize], holes: &mut Vec<Hole>) -> Vec<Attribute> {
+    attributes.iter()
+        .filter(|attribute| match attribute {
+            SnaxAttribute::Simple { value: AttributeValue::Bool { value, .. }, .. } => *value,
+            _ => true,
+        })
+        .map(|attribute| match attribute {
+            SnaxAttribute::Simple { name, value } => {
+                let name = name.to_string();
+
+                // `true` is lowered like a flag, and `false` was dropped above.
+                if let AttributeValue::Bool { .. } = value {
+                    return Attribute::Static {
+                        name,
+                        value: None,
+                    };
+                }
+
+                if let Some(text) = value.literal_text() {
+                    return Attribute::Static {
+                        name,
+                        value: Some(text),
+                    };
+                }
+
+                let hole = holes.len();
+                holes.push(Hole {
+                    path: path.to_vec(),
+                    kind: HoleKind::Attribute {
+                        name: name.clone(),
+                        value: value.expression(),
+                    },
+                    span: attribute.span(),
+                });
+
+                Attribute::Dynamic { name, hole }
+            },
+            SnaxAttribute::Flag { name } => Attribute::Static {
+                name: name.to_string(),
+                value: None,
+            },
+            SnaxAttribute::Spread { value, span } => {
+                let hole = holes.len();
+                holes.push(Hole {
+                    path: path.to_vec(),
+                    kind: HoleKind::Spread {
+                        value: value.clone(),
+                    },
+                    span: *span,
+                });
+
+                Attribute::Spread { hole }
+            },
+            SnaxAttribute::Expression { name, value } => {
+                let name = name.to_string();
+
+                let hole = holes.len();
+                holes.push(Hole {
+                    path: path.to_vec(),
+                    kind: lower_expression(Some(name.clone()), value),
+                    span: attribute.span(),
+                });
+
+                Attribute::Dynamic { name, hole }
+            },
+            SnaxAttribute::Markup { name, value } => {
+                let name = name.to_string();
+
+                let hole = holes.len();
+                holes.push(Hole {
+                    path: path.to_vec(),
+                    kind: HoleKind::Markup {
+                        name: name.clone(),
+                        template: lower(value),
+                    },
+                    span: attribute.span(),
+                });
+
+                Attribute::Dynamic { name, hole }
+            },
+        })
+        .collect()
+}
+
+fn static_text(value: &TokenTree) -> Option<String> {
+    match value {
+        TokenTree::Literal(literal) => literal_text(literal),
+        _ => None,
+    }
+}
+
+/// Moves the outermost elements that don't contain any holes into `statics`.
+fn hoist(node: &mut Node, statics: &mut Vec<Element>) {
+    let element = match node {
+        Node::Element(element) => element,
+        _ => return,
+    };
+
+    if is_static(element) {
+        let index = statics.len();
+        let element = std::mem::replace(node, Node::Static(index));
+
+        if let Node::Element(element) = element {
+            statics.push(element);
+        }
+
+        return;
+    }
+
+    for child in &mut element.children {
+        hoist(child, statics);
+    }
+}
+
+fn is_static(element: &Element) -> bool {
+    let attributes_static = element.attributes.iter()
+        .all(|attribute| matches!(attribute, Attribute::Static { .. }));
+
+    let children_static = element.children.iter()
+        .all(|child| match child {
+            Node::Element(child) => is_static(child),
+            Node::Text(_) => true,
+            Node::Hole(_) | Node::Static(_) => false,
+        });
+
+    attributes_static && children_static
+}
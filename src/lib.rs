@@ -7,7 +7,13 @@
 //! source of the ritz crate][ritz-github].
 //!
 //! ## Requirements
-//! Snax requires Rust 1.32 or newer.
+//! Snax requires Rust 1.65 or newer.
+//!
+//! ## Features
+//! - `quote`: Implements `quote::ToTokens` for the syntax tree, which turns it
+//!   back into markup.
+//! - `syn`: Implements `syn::parse::Parse` for the syntax tree, so that markup
+//!   can be embedded in larger grammars.
 //!
 //! ## License
 //! Snax is available under the MIT license. See [LICENSE.txt](LICENSE.txt) for
@@ -16,34 +22,256 @@
 //! [jsx-intro]: https://reactjs.org/docs/introducing-jsx.html
 //! [ritz-github]: https://github.com/LPGhatguy/ritz
 
+mod literal;
+mod select;
 mod tokenizer;
 
+pub mod codegen;
+pub mod cst;
+pub mod fold;
+pub mod html;
+pub mod ir;
+pub mod visit;
+pub mod visit_mut;
+
+#[cfg(feature = "quote")]
+mod quote_support;
+
+#[cfg(feature = "syn")]
+mod syn_support;
+
+use std::{
+    fmt,
+    iter::Peekable,
+};
+
 use proc_macro2::{
     TokenStream,
     TokenTree,
     Ident,
+    Punct,
+    Group,
+    Literal,
+    Delimiter,
+    Spacing,
+    Span,
 };
 
 use crate::tokenizer::{
     HtmlToken,
     HtmlOpenToken,
+    HtmlPartialTag,
+    HtmlSelfClosingToken,
+    HtmlTagEnd,
     TokenizeError,
+    is_punct,
+    parse_block,
+    parse_block_head,
+    parse_comma,
+    parse_control_keyword,
+    parse_else,
+    parse_else_if,
+    parse_for_pattern,
     parse_html_token,
+    parse_if_condition,
+    parse_match_arm_head,
+    parse_optional_block,
+    peek_control_flow,
 };
 
+use crate::literal::literal_text;
+use crate::visit_mut::VisitMut;
+
+pub use crate::select::{select, SelectorError};
+
+/// The name of a tag or attribute.
+///
+/// HTML names can contain characters that aren't valid in Rust identifiers, so
+/// names are made up of one or more idents joined by `-` or `:`:
+///
+/// ```html
+/// <my-widget data-id="1" xlink:href="#a" />
+///  ^^^^^^^^^ ^^^^^^^     ^^^^^^^^^^
+/// ```
+///
+/// Use `to_string()` to get the name as it would be written in HTML.
+#[derive(Debug, Clone)]
+pub struct SnaxName {
+    pub first: Ident,
+    pub rest: Vec<(SnaxNameSeparator, Ident)>,
+}
+
+impl SnaxName {
+    /// Returns a span covering the entire name. If spans can't be joined, like
+    /// on stable Rust, this is the span of the first ident.
+    pub fn span(&self) -> Span {
+        let first = self.first.span();
+
+        match self.rest.last() {
+            Some((_, last)) => join_spans(first, last.span()),
+            None => first,
+        }
+    }
+}
+
+impl From<Ident> for SnaxName {
+    fn from(ident: Ident) -> SnaxName {
+        SnaxName {
+            first: ident,
+            rest: Vec::new(),
+        }
+    }
+}
+
+impl PartialEq for SnaxName {
+    fn eq(&self, other: &Self) -> bool {
+        self.first == other.first && self.rest == other.rest
+    }
+}
+
+impl fmt::Display for SnaxName {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.first)?;
+
+        for (separator, ident) in &self.rest {
+            write!(formatter, "{}{}", separator.as_char(), ident)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The name of a tag, which is either a single [`SnaxName`] or a Rust path made
+/// up of names joined by `::`:
+///
+/// ```html
+/// <crate::widgets::Button />
+///  ^^^^^^^^^^^^^^^^^^^^^^
+/// ```
+///
+/// Use `to_string()` to get the path as it was written.
+///
+/// [`SnaxName`]: struct.SnaxName.html
+#[derive(Debug, Clone)]
+pub struct SnaxPath {
+    pub first: SnaxName,
+    pub rest: Vec<SnaxName>,
+}
+
+impl SnaxPath {
+    /// Returns every segment of the path in order.
+    pub fn segments(&self) -> impl Iterator<Item = &SnaxName> {
+        std::iter::once(&self.first).chain(self.rest.iter())
+    }
+
+    /// Returns the name of the tag if it isn't a path with more than one
+    /// segment. This is useful for telling HTML elements apart from components.
+    pub fn as_name(&self) -> Option<&SnaxName> {
+        if self.rest.is_empty() {
+            Some(&self.first)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a span covering the entire path. If spans can't be joined, like
+    /// on stable Rust, this is the span of the first segment.
+    pub fn span(&self) -> Span {
+        let first = self.first.span();
+
+        match self.rest.last() {
+            Some(last) => join_spans(first, last.span()),
+            None => first,
+        }
+    }
+}
+
+impl From<Ident> for SnaxPath {
+    fn from(ident: Ident) -> SnaxPath {
+        SnaxPath::from(SnaxName::from(ident))
+    }
+}
+
+impl From<SnaxName> for SnaxPath {
+    fn from(name: SnaxName) -> SnaxPath {
+        SnaxPath {
+            first: name,
+            rest: Vec::new(),
+        }
+    }
+}
+
+impl PartialEq for SnaxPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.first == other.first && self.rest == other.rest
+    }
+}
+
+impl fmt::Display for SnaxPath {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.first)?;
+
+        for segment in &self.rest {
+            write!(formatter, "::{}", segment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the expression held by content or an attribute value, which is the
+/// inside of a `{}` block or the token itself for anything else.
+pub(crate) fn value_expression(value: &TokenTree) -> TokenStream {
+    match value {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => group.stream(),
+        other => other.clone().into(),
+    }
+}
+
+/// Joins two spans into one covering both of them, falling back to the first
+/// span when joining isn't supported.
+pub(crate) fn join_spans(first: Span, last: Span) -> Span {
+    first.join(last).unwrap_or(first)
+}
+
+/// The punctuation joining two idents in a [`SnaxName`].
+///
+/// [`SnaxName`]: struct.SnaxName.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnaxNameSeparator {
+    /// `-`, like in `data-id`
+    Dash,
+
+    /// `:`, like in `xlink:href`
+    Colon,
+}
+
+impl SnaxNameSeparator {
+    pub fn as_char(self) -> char {
+        match self {
+            SnaxNameSeparator::Dash => '-',
+            SnaxNameSeparator::Colon => ':',
+        }
+    }
+}
+
 /// An attribute that's present on either a [`SnaxTag`] or a
 /// [`SnaxSelfClosingTag`].
 ///
-/// Attributes can only be `Simple` right now, which is a name-value pair where
-/// the name is a fixed ident and the value is either a Literal or a Group.
+/// Attributes are either `Simple`, which is a name-value pair where the name is
+/// a [`SnaxName`] and the value is an [`AttributeValue`], `Flag`, which is
+/// a name without a value, `Spread`, which splats an expression into the
+/// attribute list, `Markup`, whose value is an element or fragment, or
+/// `Expression`, which is like `Simple` but has markup embedded in its value.
 ///
-/// In the future, snax_syntax will support attribute spreading. See [issue
-/// #4](https://github.com/LPGhatguy/snax/issues/4) for more details and
-/// progress updates.
+/// Attributes are kept in the order they were written in, so consumers can
+/// decide how a spread interacts with attributes that come before or after it.
 ///
+/// [`SnaxName`]: struct.SnaxName.html
+/// [`AttributeValue`]: enum.AttributeValue.html
 /// [`SnaxTag`]: struct.SnaxTag.html
 /// [`SnaxSelfClosingTag`]: struct.SnaxSelfClosingTag.html
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SnaxAttribute {
     /// A name-value pair describing a property.
     ///
@@ -51,8 +279,8 @@ pub enum SnaxAttribute {
     /// <div foo="bar" />
     ///      ^^^^^^^^^
     ///      SnaxAttribute::Simple {
-    ///          name: Ident(foo),
-    ///          value: TokenTree("bar"),
+    ///          name: SnaxName(foo),
+    ///          value: AttributeValue::Literal("bar"),
     ///      }
     /// ```
     ///
@@ -60,16 +288,301 @@ pub enum SnaxAttribute {
     /// <div hello={ "world" }>"hey there"</div>
     ///      ^^^^^^^^^^^^^^^^^
     ///      SnaxAttribute::Simple {
-    ///          name: Ident(hello),
-    ///          value: TokenTree({ "world" }),
+    ///          name: SnaxName(hello),
+    ///          value: AttributeValue::Block({ "world" }),
     ///      }
     /// ```
+    ///
+    /// A single identifier in braces is shorthand for an attribute whose value
+    /// is a variable of the same name. Both the name and the value keep the
+    /// identifier's span.
+    ///
+    /// ```html
+    /// <Input {value} />
+    ///        ^^^^^^^
+    ///        SnaxAttribute::Simple {
+    ///            name: SnaxName(value),
+    ///            value: AttributeValue::Path { first: value, rest: [] },
+    ///        }
+    /// ```
     Simple {
-        name: Ident,
-        value: TokenTree,
+        name: SnaxName,
+        value: AttributeValue,
+    },
+
+    /// A name without a value, like HTML's boolean attributes.
+    ///
+    /// ```html
+    /// <input disabled />
+    ///        ^^^^^^^^
+    ///        SnaxAttribute::Flag {
+    ///            name: SnaxName(disabled),
+    ///        }
+    /// ```
+    Flag {
+        name: SnaxName,
+    },
+
+    /// An expression whose properties should be spread into the attribute
+    /// list. Both `..` and JSX's `...` are accepted.
+    ///
+    /// ```html
+    /// <Button {..props} label="x" />
+    ///         ^^^^^^^^^
+    ///         SnaxAttribute::Spread {
+    ///             value: TokenStream(props),
+    ///             span: Span({..props}),
+    ///         }
+    /// ```
+    Spread {
+        value: TokenStream,
+        span: Span,
+    },
+
+    /// A name-value pair whose value is an element or a fragment, which is
+    /// useful for passing markup to named slots of a component.
+    ///
+    /// ```html
+    /// <Layout header=<Header title="Home" /> >
+    ///         ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    ///         SnaxAttribute::Markup {
+    ///             name: SnaxName(header),
+    ///             value: SnaxItem(<Header title="Home" />),
+    ///         }
+    /// ```
+    Markup {
+        name: SnaxName,
+        value: Box<SnaxItem>,
+    },
+
+    /// A name-value pair whose value is a block with markup embedded in it.
+    /// These are only produced by [`parse_embedded`].
+    ///
+    /// ```html
+    /// <Table row={ |user| <Row user={ user } /> } />
+    ///        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    ///        SnaxAttribute::Expression {
+    ///            name: SnaxName(row),
+    ///            value: SnaxExpression({ |user| <Row ... /> }),
+    ///        }
+    /// ```
+    ///
+    /// [`parse_embedded`]: fn.parse_embedded.html
+    Expression {
+        name: SnaxName,
+        value: SnaxExpression,
+    },
+}
+
+impl SnaxAttribute {
+    /// Returns a span covering the whole attribute, or the span of its name if
+    /// spans can't be joined.
+    pub fn span(&self) -> Span {
+        match self {
+            SnaxAttribute::Simple { name, value } => join_spans(name.span(), value.span()),
+            SnaxAttribute::Flag { name } => name.span(),
+            SnaxAttribute::Spread { span, .. } => *span,
+            SnaxAttribute::Markup { name, value } => join_spans(name.span(), value.span()),
+            SnaxAttribute::Expression { name, value } => join_spans(name.span(), value.span()),
+        }
+    }
+
+    /// Returns the name of the attribute, or `None` for a spread.
+    pub fn name(&self) -> Option<&SnaxName> {
+        match self {
+            SnaxAttribute::Simple { name, .. } => Some(name),
+            SnaxAttribute::Flag { name } => Some(name),
+            SnaxAttribute::Spread { .. } => None,
+            SnaxAttribute::Markup { name, .. } => Some(name),
+            SnaxAttribute::Expression { name, .. } => Some(name),
+        }
+    }
+}
+
+/// The value of a [`SnaxAttribute::Simple`], which is everything after the
+/// `=`. Values other than blocks can be written without braces:
+///
+/// ```html
+/// <Slider label="Volume" min=-1 max={ limit } snap=true kind=Kind::Linear />
+/// ```
+///
+/// Use `to_string()` to get the value as it was written.
+///
+/// [`SnaxAttribute::Simple`]: enum.SnaxAttribute.html#variant.Simple
+#[derive(Debug, Clone)]
+pub enum AttributeValue {
+    /// A string, character, or numeric literal, like `"Volume"` or `5`.
+    Literal(Literal),
+
+    /// A numeric literal with a leading minus sign, like `-1`.
+    Negative {
+        minus: Punct,
+        literal: Literal,
+    },
+
+    /// A group, which is usually a block like `{ limit }`.
+    Block(Group),
+
+    /// `true` or `false`.
+    Bool {
+        value: bool,
+        span: Span,
+    },
+
+    /// A path without generic arguments, like `Kind::Linear` or `value`.
+    Path {
+        first: Ident,
+        rest: Vec<Ident>,
     },
 }
 
+impl AttributeValue {
+    /// Returns a span covering the entire value. If spans can't be joined, this
+    /// is the span of its first token.
+    pub fn span(&self) -> Span {
+        match self {
+            AttributeValue::Literal(literal) => literal.span(),
+            AttributeValue::Negative { minus, literal } => join_spans(minus.span(), literal.span()),
+            AttributeValue::Block(group) => group.span(),
+            AttributeValue::Bool { span, .. } => *span,
+            AttributeValue::Path { first, rest } => match rest.last() {
+                Some(last) => join_spans(first.span(), last.span()),
+                None => first.span(),
+            },
+        }
+    }
+
+    /// Returns the Rust expression this value stands for, which is the inside
+    /// of a block or the value's own tokens for anything else.
+    pub fn expression(&self) -> TokenStream {
+        match self {
+            AttributeValue::Block(group) if group.delimiter() == Delimiter::Brace => group.stream(),
+            other => other.tokens(),
+        }
+    }
+
+    /// Returns the tokens the value was written as.
+    pub(crate) fn tokens(&self) -> TokenStream {
+        match self {
+            AttributeValue::Literal(literal) => TokenTree::Literal(literal.clone()).into(),
+            AttributeValue::Negative { minus, literal } => {
+                vec![TokenTree::Punct(minus.clone()), TokenTree::Literal(literal.clone())]
+                    .into_iter()
+                    .collect()
+            },
+            AttributeValue::Block(group) => TokenTree::Group(group.clone()).into(),
+            AttributeValue::Bool { value, span } => {
+                let name = if *value { "true" } else { "false" };
+                TokenTree::Ident(Ident::new(name, *span)).into()
+            },
+            AttributeValue::Path { first, rest } => {
+                let mut tokens = vec![TokenTree::Ident(first.clone())];
+
+                for segment in rest {
+                    let mut joint = Punct::new(':', Spacing::Joint);
+                    let mut alone = Punct::new(':', Spacing::Alone);
+                    joint.set_span(segment.span());
+                    alone.set_span(segment.span());
+
+                    tokens.push(TokenTree::Punct(joint));
+                    tokens.push(TokenTree::Punct(alone));
+                    tokens.push(TokenTree::Ident(segment.clone()));
+                }
+
+                tokens.into_iter().collect()
+            },
+        }
+    }
+
+    /// Returns the text a literal or negative literal stands for, or `None` for
+    /// values that need to be evaluated at runtime.
+    pub(crate) fn literal_text(&self) -> Option<String> {
+        match self {
+            AttributeValue::Literal(literal) => literal_text(literal),
+            AttributeValue::Negative { literal, .. } => {
+                literal_text(literal).map(|text| format!("-{}", text))
+            },
+            _ => None,
+        }
+    }
+}
+
+impl From<Literal> for AttributeValue {
+    fn from(literal: Literal) -> AttributeValue {
+        AttributeValue::Literal(literal)
+    }
+}
+
+impl From<Group> for AttributeValue {
+    fn from(group: Group) -> AttributeValue {
+        AttributeValue::Block(group)
+    }
+}
+
+impl PartialEq for AttributeValue {
+    fn eq(&self, other: &Self) -> bool {
+        use AttributeValue::*;
+
+        match (self, other) {
+            (Literal(literal), Literal(other_literal)) => {
+                literal.to_string() == other_literal.to_string()
+            },
+            (
+                Negative { literal, .. },
+                Negative { literal: other_literal, .. },
+            ) => {
+                literal.to_string() == other_literal.to_string()
+            },
+            (Block(group), Block(other_group)) => {
+                group.to_string() == other_group.to_string()
+            },
+            (
+                Bool { value, .. },
+                Bool { value: other_value, .. },
+            ) => {
+                value == other_value
+            },
+            (
+                Path { first, rest },
+                Path { first: other_first, rest: other_rest },
+            ) => {
+                first == other_first && rest == other_rest
+            },
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttributeValue::Literal(literal) => write!(formatter, "{}", literal),
+            AttributeValue::Negative { literal, .. } => write!(formatter, "-{}", literal),
+            AttributeValue::Block(group) => write!(formatter, "{}", group),
+            AttributeValue::Bool { value, .. } => write!(formatter, "{}", value),
+            AttributeValue::Path { first, rest } => {
+                write!(formatter, "{}", first)?;
+
+                for segment in rest {
+                    write!(formatter, "::{}", segment)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Finds the value of the first `Simple` attribute with the given name.
+fn find_attribute<'a>(attributes: &'a [SnaxAttribute], name: &str) -> Option<&'a AttributeValue> {
+    attributes.iter().find_map(|attribute| match attribute {
+        SnaxAttribute::Simple { name: attribute_name, value } if attribute_name.to_string() == name => {
+            Some(value)
+        },
+        _ => None,
+    })
+}
+
 impl PartialEq for SnaxAttribute {
     fn eq(&self, other: &Self) -> bool {
         use SnaxAttribute::*;
@@ -80,8 +593,35 @@ impl PartialEq for SnaxAttribute {
                 Simple { name: other_name, value: other_value },
             ) => {
                 name == other_name
-                && value.to_string() == other_value.to_string()
+                && value == other_value
+            },
+            (
+                Flag { name },
+                Flag { name: other_name },
+            ) => {
+                name == other_name
+            },
+            (
+                Spread { value, .. },
+                Spread { value: other_value, .. },
+            ) => {
+                value.to_string() == other_value.to_string()
+            },
+            (
+                Markup { name, value },
+                Markup { name: other_name, value: other_value },
+            ) => {
+                name == other_name
+                && value == other_value
+            },
+            (
+                Expression { name, value },
+                Expression { name: other_name, value: other_value },
+            ) => {
+                name == other_name
+                && value == other_value
             },
+            _ => false,
         }
     }
 }
@@ -90,7 +630,7 @@ impl PartialEq for SnaxAttribute {
 ///
 /// For more information, look at the documentation for the struct that each
 /// variant wraps.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SnaxItem {
     /// A standard tag, which can have attributes and children.
     Tag(SnaxTag),
@@ -103,6 +643,145 @@ pub enum SnaxItem {
 
     /// A block of content, which can contain any Rust expression.
     Content(TokenTree),
+
+    /// A block of content with markup embedded in it, produced only by
+    /// [`parse_embedded`].
+    ///
+    /// [`parse_embedded`]: fn.parse_embedded.html
+    Expression(SnaxExpression),
+
+    /// Conditional markup, written with `@if`.
+    If(SnaxIf),
+
+    /// Markup repeated for each item of an iterator, written with `@for`.
+    For(SnaxFor),
+
+    /// Markup chosen by pattern matching, written with `@match`.
+    Match(SnaxMatch),
+}
+
+impl SnaxItem {
+    /// Returns a span covering the whole item. See the `span` method on each
+    /// node type for details.
+    pub fn span(&self) -> Span {
+        match self {
+            SnaxItem::Tag(tag) => tag.span(),
+            SnaxItem::SelfClosingTag(tag) => tag.span(),
+            SnaxItem::Fragment(fragment) => fragment.span(),
+            SnaxItem::Content(content) => content.span(),
+            SnaxItem::Expression(expression) => expression.span(),
+            SnaxItem::If(node) => node.span(),
+            SnaxItem::For(node) => node.span(),
+            SnaxItem::Match(node) => node.span(),
+        }
+    }
+
+    /// Returns the name of the item if it's a tag.
+    pub fn name(&self) -> Option<&SnaxPath> {
+        match self {
+            SnaxItem::Tag(tag) => Some(&tag.name),
+            SnaxItem::SelfClosingTag(tag) => Some(&tag.name),
+            _ => None,
+        }
+    }
+
+    /// Returns the direct children of a tag or fragment. Self-closing tags and
+    /// content have no children.
+    ///
+    /// Markup embedded in an expression isn't counted as a child, since the
+    /// expression decides whether and how often it's used.
+    ///
+    /// Control-flow items can have more than one list of children, so they
+    /// return an empty list here. Use [`bodies`](#method.bodies) to get them.
+    pub fn children(&self) -> &[SnaxItem] {
+        match self {
+            SnaxItem::Tag(tag) => &tag.children,
+            SnaxItem::Fragment(fragment) => &fragment.children,
+            _ => &[],
+        }
+    }
+
+    /// Returns every list of items nested directly inside this one. This is
+    /// the children of a tag or fragment, and each branch of a control-flow
+    /// item.
+    pub fn bodies(&self) -> Vec<&[SnaxItem]> {
+        match self {
+            SnaxItem::Tag(tag) => vec![&tag.children],
+            SnaxItem::Fragment(fragment) => vec![&fragment.children],
+            SnaxItem::SelfClosingTag(_) | SnaxItem::Content(_) | SnaxItem::Expression(_) => Vec::new(),
+            SnaxItem::If(node) => {
+                let mut bodies: Vec<&[SnaxItem]> = vec![&node.then_branch];
+                bodies.extend(node.else_branch.as_deref());
+                bodies
+            },
+            SnaxItem::For(node) => vec![&node.body],
+            SnaxItem::Match(node) => node.arms.iter()
+                .map(|arm| arm.body.as_slice())
+                .collect(),
+        }
+    }
+
+    /// Iterates over every item nested inside this one, depth-first and in
+    /// pre-order, including the branches of control-flow items. The item
+    /// itself isn't included.
+    pub fn descendants(&self) -> Descendants<'_> {
+        let mut descendants = Descendants {
+            stack: Vec::new(),
+        };
+
+        descendants.push_bodies(self);
+        descendants
+    }
+
+    /// Iterates over this item and its descendants, yielding every tag whose
+    /// name is written as `name`, like `"Button"` or `"ui::Button"`.
+    pub fn find_by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a SnaxItem> + 'a {
+        std::iter::once(self)
+            .chain(self.descendants())
+            .filter(move |item| match item.name() {
+                Some(item_name) => item_name.to_string() == name,
+                None => false,
+            })
+    }
+}
+
+/// Iterator over the descendants of a [`SnaxItem`], created by
+/// [`SnaxItem::descendants`].
+///
+/// [`SnaxItem`]: enum.SnaxItem.html
+/// [`SnaxItem::descendants`]: enum.SnaxItem.html#method.descendants
+#[derive(Debug, Clone)]
+pub struct Descendants<'a> {
+    stack: Vec<std::slice::Iter<'a, SnaxItem>>,
+}
+
+impl<'a> Descendants<'a> {
+    /// Queues up the bodies of an item so that the first one is visited first.
+    fn push_bodies(&mut self, item: &'a SnaxItem) {
+        for body in item.bodies().into_iter().rev() {
+            self.stack.push(body.iter());
+        }
+    }
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = &'a SnaxItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let siblings = self.stack.last_mut()?;
+
+            match siblings.next() {
+                Some(item) => {
+                    self.push_bodies(item);
+                    return Some(item);
+                },
+                None => {
+                    self.stack.pop();
+                },
+            }
+        }
+    }
 }
 
 impl PartialEq for SnaxItem {
@@ -116,6 +795,10 @@ impl PartialEq for SnaxItem {
             (Content(this), Content(other)) => {
                 this.to_string() == other.to_string()
             },
+            (Expression(this), Expression(other)) => this == other,
+            (If(this), If(other)) => this == other,
+            (For(this), For(other)) => this == other,
+            (Match(this), Match(other)) => this == other,
             _ => false,
         }
     }
@@ -126,11 +809,49 @@ impl PartialEq for SnaxItem {
 /// ```html
 /// <div hello="world">"Hey!"</div>
 /// ```
-#[derive(Debug, PartialEq)]
+///
+/// Component tags can have turbofish generic arguments, which are stored in
+/// `generics` without their surrounding angle brackets. The closing tag can
+/// either repeat them or leave them off:
+///
+/// ```html
+/// <List::<User> items={users}></List>
+/// ```
+///
+/// `open_span` covers the opening tag from `<` to `>`, and `close_span` covers
+/// the closing tag. Spans are ignored when comparing tags.
+#[derive(Debug, Clone)]
 pub struct SnaxTag {
-    pub name: Ident,
+    pub name: SnaxPath,
+    pub generics: Option<TokenStream>,
     pub attributes: Vec<SnaxAttribute>,
     pub children: Vec<SnaxItem>,
+    pub open_span: Span,
+    pub close_span: Span,
+}
+
+impl SnaxTag {
+    /// Returns a span covering the entire element, from the start of the
+    /// opening tag to the end of the closing tag. If spans can't be joined,
+    /// this is the span of the opening tag.
+    pub fn span(&self) -> Span {
+        join_spans(self.open_span, self.close_span)
+    }
+
+    /// Returns the value of the attribute named `name`, like `"class"` or
+    /// `"data-id"`. Flags and spreads have no value and are never returned.
+    pub fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        find_attribute(&self.attributes, name)
+    }
+}
+
+impl PartialEq for SnaxTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+        && streams_eq(&self.generics, &other.generics)
+        && self.attributes == other.attributes
+        && self.children == other.children
+    }
 }
 
 /// A self-closing tag, which doesn't have children:
@@ -142,10 +863,47 @@ pub struct SnaxTag {
 /// Note that snax_syntax does not support automatically closing unclosed
 /// tags like HTML does, such as `<br>`. These tags need to be written as
 /// `<br />` in order to simplify parsing.
-#[derive(Debug, PartialEq)]
-pub struct SnaxSelfClosingTag {
-    pub name: Ident,
+///
+/// Like [`SnaxTag`], self-closing tags can have turbofish generic arguments.
+///
+/// [`SnaxTag`]: struct.SnaxTag.html
+#[derive(Debug, Clone)]
+pub struct SnaxSelfClosingTag {
+    pub name: SnaxPath,
+    pub generics: Option<TokenStream>,
     pub attributes: Vec<SnaxAttribute>,
+    pub span: Span,
+}
+
+impl SnaxSelfClosingTag {
+    /// Returns a span covering the entire tag, from `<` to `/>`.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the value of the attribute named `name`. See
+    /// [`SnaxTag::attribute`](struct.SnaxTag.html#method.attribute).
+    pub fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        find_attribute(&self.attributes, name)
+    }
+}
+
+impl PartialEq for SnaxSelfClosingTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+        && streams_eq(&self.generics, &other.generics)
+        && self.attributes == other.attributes
+    }
+}
+
+/// Compares two optional token streams by their printed form, since token
+/// streams don't implement `PartialEq`.
+fn streams_eq(this: &Option<TokenStream>, other: &Option<TokenStream>) -> bool {
+    match (this, other) {
+        (Some(this), Some(other)) => this.to_string() == other.to_string(),
+        (None, None) => true,
+        _ => false,
+    }
 }
 
 /// A fragment, which only contains children.
@@ -159,32 +917,443 @@ pub struct SnaxSelfClosingTag {
 ///
 /// This syntax comes from JSX, and in frameworks like React, it's expected that
 /// the children of a fragment will be merged into the fragment's parent.
-#[derive(Debug, PartialEq)]
+///
+/// `open_span` covers `<>` and `close_span` covers `</>`. Spans are ignored
+/// when comparing fragments.
+#[derive(Debug, Clone)]
 pub struct SnaxFragment {
     pub children: Vec<SnaxItem>,
+    pub open_span: Span,
+    pub close_span: Span,
+}
+
+impl SnaxFragment {
+    /// Returns a span covering the entire fragment. If spans can't be joined,
+    /// this is the span of `<>`.
+    pub fn span(&self) -> Span {
+        join_spans(self.open_span, self.close_span)
+    }
+}
+
+impl PartialEq for SnaxFragment {
+    fn eq(&self, other: &Self) -> bool {
+        self.children == other.children
+    }
+}
+
+/// Conditional markup:
+///
+/// ```html
+/// @if user.is_admin {
+///     <AdminPanel />
+/// } else if let Some(name) = &user.name {
+///     <p>"Hi, " { name }</p>
+/// } else {
+///     <LogIn />
+/// }
+/// ```
+///
+/// The condition is kept as written, so it can be anything Rust allows after
+/// `if`, including `let` patterns. The branches are parsed as markup.
+///
+/// An `else if` is stored as an `else` branch containing a single `SnaxIf`,
+/// the same as if it had been written as `else { @if ... }`.
+#[derive(Debug, Clone)]
+pub struct SnaxIf {
+    pub condition: TokenStream,
+    pub then_branch: Vec<SnaxItem>,
+    pub else_branch: Option<Vec<SnaxItem>>,
+    pub span: Span,
+}
+
+impl SnaxIf {
+    /// Returns a span covering the entire `@if`, from `@` to the end of its
+    /// last branch.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl PartialEq for SnaxIf {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition.to_string() == other.condition.to_string()
+        && self.then_branch == other.then_branch
+        && self.else_branch == other.else_branch
+    }
+}
+
+/// Markup repeated for each item of an iterator:
+///
+/// ```html
+/// @for (index, user) in users.iter().enumerate() {
+///     <li key={ index }>{ &user.name }</li>
+/// }
+/// ```
+///
+/// The pattern and iterator are kept as written, and the body is parsed as
+/// markup.
+#[derive(Debug, Clone)]
+pub struct SnaxFor {
+    pub pattern: TokenStream,
+    pub iterator: TokenStream,
+    pub body: Vec<SnaxItem>,
+    pub span: Span,
+}
+
+impl SnaxFor {
+    /// Returns a span covering the entire `@for`, from `@` to the end of its
+    /// body.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl PartialEq for SnaxFor {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.to_string() == other.pattern.to_string()
+        && self.iterator.to_string() == other.iterator.to_string()
+        && self.body == other.body
+    }
+}
+
+/// Markup chosen by pattern matching:
+///
+/// ```html
+/// @match status {
+///     Status::Loading => <Spinner />,
+///     Status::Failed(error) if error.is_fatal() => {
+///         <h1>"Oh no!"</h1>
+///         <p>{ error }</p>
+///     },
+///     _ => <></>,
+/// }
+/// ```
+///
+/// Each arm's body is either a single item or a block of any number of items.
+#[derive(Debug, Clone)]
+pub struct SnaxMatch {
+    pub expression: TokenStream,
+    pub arms: Vec<SnaxMatchArm>,
+    pub span: Span,
+}
+
+impl SnaxMatch {
+    /// Returns a span covering the entire `@match`, from `@` to the closing
+    /// brace.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl PartialEq for SnaxMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.expression.to_string() == other.expression.to_string()
+        && self.arms == other.arms
+    }
+}
+
+/// One arm of a [`SnaxMatch`]. A body written as a single item is stored as a
+/// list containing just that item.
+///
+/// [`SnaxMatch`]: struct.SnaxMatch.html
+#[derive(Debug, Clone)]
+pub struct SnaxMatchArm {
+    pub pattern: TokenStream,
+    pub guard: Option<TokenStream>,
+    pub body: Vec<SnaxItem>,
+}
+
+impl PartialEq for SnaxMatchArm {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.to_string() == other.pattern.to_string()
+        && streams_eq(&self.guard, &other.guard)
+        && self.body == other.body
+    }
+}
+
+/// A Rust expression with markup embedded in it, like the body of this block:
+///
+/// ```html
+/// <ul>{ users.iter().map(|user| <li>{ &user.name }</li>) }</ul>
+/// ```
+///
+/// The tokens of the block are kept as written, except that each piece of
+/// embedded markup is parsed into a [`SnaxItem`]. Nested groups containing
+/// markup, like the parentheses around the closure above, become nested
+/// `SnaxExpression`s. Code generators can use [`substitute`] to replace each
+/// item with code of their own.
+///
+/// [`SnaxItem`]: enum.SnaxItem.html
+/// [`substitute`]: #method.substitute
+#[derive(Debug, Clone)]
+pub struct SnaxExpression {
+    pub delimiter: Delimiter,
+    pub parts: Vec<SnaxExpressionPart>,
+    pub span: Span,
+}
+
+impl SnaxExpression {
+    /// Returns the span of the group, including its delimiters.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns every embedded item, including ones in nested groups, in the
+    /// order that they were written.
+    pub fn items(&self) -> Vec<&SnaxItem> {
+        let mut items = Vec::new();
+
+        for part in &self.parts {
+            match part {
+                SnaxExpressionPart::Token(_) => {},
+                SnaxExpressionPart::Group(group) => items.extend(group.items()),
+                SnaxExpressionPart::Item(item) => items.push(item),
+            }
+        }
+
+        items
+    }
+
+    /// Turns the expression back into a group of tokens, calling
+    /// `substitution` to get the tokens that each embedded item is replaced
+    /// with. Items are visited in the order that they were written.
+    pub fn substitute<F>(&self, mut substitution: F) -> TokenTree
+        where F: FnMut(&SnaxItem) -> TokenStream
+    {
+        self.substitute_with(&mut substitution)
+    }
+
+    fn substitute_with(&self, substitution: &mut dyn FnMut(&SnaxItem) -> TokenStream) -> TokenTree {
+        let stream = self.parts.iter()
+            .flat_map(|part| -> TokenStream {
+                match part {
+                    SnaxExpressionPart::Token(token) => token.clone().into(),
+                    SnaxExpressionPart::Group(group) => group.substitute_with(substitution).into(),
+                    SnaxExpressionPart::Item(item) => substitution(item),
+                }
+            })
+            .collect();
+
+        let mut group = Group::new(self.delimiter, stream);
+        group.set_span(self.span);
+        TokenTree::Group(group)
+    }
+}
+
+impl PartialEq for SnaxExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.delimiter == other.delimiter
+        && self.parts == other.parts
+    }
 }
 
+/// One piece of a [`SnaxExpression`].
+///
+/// [`SnaxExpression`]: struct.SnaxExpression.html
+#[derive(Debug, Clone)]
+pub enum SnaxExpressionPart {
+    /// A token of Rust code. Groups that don't contain any markup are kept as
+    /// a single token.
+    Token(TokenTree),
+
+    /// A group that contains markup somewhere inside of it.
+    Group(SnaxExpression),
+
+    /// A piece of embedded markup.
+    Item(SnaxItem),
+}
+
+impl PartialEq for SnaxExpressionPart {
+    fn eq(&self, other: &Self) -> bool {
+        use SnaxExpressionPart::*;
+
+        match (self, other) {
+            (Token(this), Token(other)) => this.to_string() == other.to_string(),
+            (Group(this), Group(other)) => this == other,
+            (Item(this), Item(other)) => this == other,
+            _ => false,
+        }
+    }
+}
+
+/// An error encountered while parsing.
+///
+/// Macro authors will usually want to turn errors into a `compile_error!`
+/// invocation pointing at the problem with [`to_compile_error`].
+///
+/// [`to_compile_error`]: #method.to_compile_error
 #[derive(Debug)]
 pub enum ParseError {
-    UnexpectedEnd,
-    UnexpectedItem(HtmlToken),
-    UnexpectedToken(TokenTree),
+    /// The input ended partway through a token.
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+
+    /// A complete token was found somewhere it isn't allowed, like a closing
+    /// fragment that closes a tag.
+    UnexpectedItem(Box<HtmlToken>),
+
+    /// A token that doesn't fit the syntax was found.
+    UnexpectedToken {
+        token: TokenTree,
+        expected: &'static str,
+    },
+
+    /// A closing tag doesn't match the tag it closes.
+    ///
+    /// ```html
+    /// <div></span>
+    /// ```
+    ///
+    /// Generic arguments are kept too, since they can be the only difference
+    /// between the two tags, like in `<List::<A>></List::<B>>`.
+    MismatchedCloseTag {
+        open: Box<SnaxPath>,
+        open_generics: Option<TokenStream>,
+        close: Box<SnaxPath>,
+        close_generics: Option<TokenStream>,
+    },
+
+    /// The input ended while tags or fragments were still open.
+    ///
+    /// `open` lists everything that's still open, outermost first. Fragments
+    /// are listed as `None`.
+    UnclosedTag {
+        open: Vec<Option<SnaxPath>>,
+    },
+}
+
+impl ParseError {
+    /// Returns the span that this error should be reported at.
+    ///
+    /// Errors caused by running out of input point at the call site, which in
+    /// a proc macro is the macro invocation.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedEnd { .. } => Span::call_site(),
+            ParseError::UnexpectedItem(item) => item.span(),
+            ParseError::UnexpectedToken { token, .. } => token.span(),
+            ParseError::MismatchedCloseTag { close, .. } => close.span(),
+            ParseError::UnclosedTag { open } => {
+                open.iter()
+                    .rev()
+                    .flatten()
+                    .next()
+                    .map(SnaxPath::span)
+                    .unwrap_or_else(Span::call_site)
+            },
+        }
+    }
+
+    /// Creates a `compile_error!` invocation describing this error, spanned so
+    /// that rustc points at the offending tokens.
+    ///
+    /// The result is a single macro invocation, so it can be used in both item
+    /// and expression position. Related code, like the opening tag of a
+    /// `MismatchedCloseTag`, is named in the message instead of getting its own
+    /// invocation.
+    pub fn to_compile_error(&self) -> TokenStream {
+        compile_error(&self.to_string(), self.span())
+    }
 }
 
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd { expected } => {
+                write!(formatter, "unexpected end of input, expected {}", expected)
+            },
+            ParseError::UnexpectedItem(item) => {
+                write!(formatter, "unexpected `{}`", item)
+            },
+            ParseError::UnexpectedToken { token, expected } => {
+                write!(formatter, "unexpected `{}`, expected {}", token, expected)
+            },
+            ParseError::MismatchedCloseTag { open, open_generics, close, close_generics } => {
+                write!(
+                    formatter,
+                    "closing tag `</{}>` does not match opening tag `<{}>`",
+                    tag_name(close, close_generics),
+                    tag_name(open, open_generics),
+                )
+            },
+            ParseError::UnclosedTag { open } => {
+                write!(formatter, "input ended before closing ")?;
+
+                for (index, name) in open.iter().enumerate() {
+                    if index > 0 {
+                        write!(formatter, ", ")?;
+                    }
+
+                    match name {
+                        Some(name) => write!(formatter, "`<{}>`", name)?,
+                        None => write!(formatter, "`<>`")?,
+                    }
+                }
+
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 impl From<TokenizeError> for ParseError {
     fn from(error: TokenizeError) -> ParseError {
         match error {
-            TokenizeError::UnexpectedEnd => ParseError::UnexpectedEnd,
-            TokenizeError::UnexpectedToken(token) => ParseError::UnexpectedToken(token),
+            TokenizeError::UnexpectedEnd { expected } => ParseError::UnexpectedEnd { expected },
+            TokenizeError::UnexpectedToken { token, expected } => ParseError::UnexpectedToken { token, expected },
+            TokenizeError::Markup(error) => *error,
+            TokenizeError::Tag { error, .. } => ParseError::from(*error),
         }
     }
 }
 
+/// Writes out a tag's name the way it was written in a tag, including generic
+/// arguments, like `List::<User>`.
+pub(crate) fn tag_name(path: &SnaxPath, generics: &Option<TokenStream>) -> String {
+    match generics {
+        Some(generics) => format!("{}::<{}>", path, generics),
+        None => path.to_string(),
+    }
+}
+
+/// Builds `::core::compile_error! { "message" }` with every token given the
+/// same span.
+pub(crate) fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+
+    let body = Group::new(Delimiter::Brace, TokenTree::Literal(message).into());
+
+    let tokens = vec![
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("core", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(body),
+    ];
+
+    tokens.into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
 macro_rules! expect_end {
     ($iterator: expr) => {
         match $iterator.next() {
             None => {},
-            Some(unexpected) => return Err(ParseError::UnexpectedToken(unexpected)),
+            Some(token) => return Err(ParseError::UnexpectedToken {
+                token,
+                expected: "the end of the input",
+            }),
         }
     };
 }
@@ -192,99 +1361,856 @@ macro_rules! expect_end {
 #[derive(Debug)]
 enum OpenToken {
     Tag(HtmlOpenToken),
-    Fragment,
+    Fragment(Span),
 }
 
-/// Attempts to parse a `proc_macro2::TokenStream` into a `SnaxItem`.
-pub fn parse(input_stream: TokenStream) -> Result<SnaxItem, ParseError> {
-    let mut input = input_stream.into_iter();
-    let mut tag_stack: Vec<(OpenToken, Vec<SnaxItem>)> = Vec::new();
+impl OpenToken {
+    fn name(&self) -> Option<SnaxPath> {
+        match self {
+            OpenToken::Tag(tag) => Some(tag.name.clone()),
+            OpenToken::Fragment(_) => None,
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            OpenToken::Tag(tag) => tag.span,
+            OpenToken::Fragment(span) => *span,
+        }
+    }
 
-    loop {
-        match parse_html_token(&mut input)? {
-            HtmlToken::OpenTag(opening_tag) => {
-                tag_stack.push((OpenToken::Tag(opening_tag), Vec::new()));
+    /// Tells whether the given closing tag or fragment closes this one.
+    fn is_closed_by(&self, closing: &HtmlToken) -> bool {
+        match (self, closing) {
+            (OpenToken::Tag(opening_tag), HtmlToken::CloseTag(closing_tag)) => {
+                // Closing tags are allowed to leave off generic arguments, but
+                // if they're present, they need to match the opening tag.
+                let generics_match = closing_tag.generics.is_none()
+                    || streams_eq(&opening_tag.generics, &closing_tag.generics);
+
+                opening_tag.name == closing_tag.name && generics_match
             },
-            HtmlToken::CloseTag(closing_tag) => {
-                let (open_token, children) = tag_stack.pop()
-                    .ok_or_else(|| ParseError::UnexpectedItem(HtmlToken::CloseTag(closing_tag.clone())))?;
+            (OpenToken::Fragment(_), HtmlToken::CloseFragment(_)) => true,
+            _ => false,
+        }
+    }
 
-                let opening_tag = match open_token {
-                    OpenToken::Tag(tag) => tag,
-                    OpenToken::Fragment => return Err(ParseError::UnexpectedItem(HtmlToken::CloseTag(closing_tag.clone()))),
-                };
+    fn finish(self, children: Vec<SnaxItem>, close_span: Span) -> SnaxItem {
+        match self {
+            OpenToken::Tag(opening_tag) => SnaxItem::Tag(SnaxTag {
+                name: opening_tag.name,
+                generics: opening_tag.generics,
+                attributes: opening_tag.attributes,
+                children,
+                open_span: opening_tag.span,
+                close_span,
+            }),
+            OpenToken::Fragment(open_span) => SnaxItem::Fragment(SnaxFragment {
+                children,
+                open_span,
+                close_span,
+            }),
+        }
+    }
+}
+
+/// Creates the error for a closing tag or fragment that doesn't close the
+/// innermost open tag, if there is one.
+fn close_error(opening: Option<&OpenToken>, closing: HtmlToken) -> ParseError {
+    match (opening, closing) {
+        (Some(OpenToken::Tag(opening_tag)), HtmlToken::CloseTag(closing_tag)) => {
+            ParseError::MismatchedCloseTag {
+                open: Box::new(opening_tag.name.clone()),
+                open_generics: opening_tag.generics.clone(),
+                close: Box::new(closing_tag.name),
+                close_generics: closing_tag.generics,
+            }
+        },
+        (_, closing) => ParseError::UnexpectedItem(Box::new(closing)),
+    }
+}
+
+type TagStack = Vec<(OpenToken, Vec<SnaxItem>)>;
+
+/// Creates the error for input that ended while everything on the stack was
+/// still open.
+fn unclosed_error(tag_stack: &TagStack) -> ParseError {
+    let open = tag_stack.iter()
+        .map(|(open_token, _)| open_token.name())
+        .collect();
+
+    ParseError::UnclosedTag { open }
+}
+
+/// Pops the innermost open tag off of the stack and adds it to its parent's
+/// children, returning it instead if it was the root.
+///
+/// Tags that are closed without a closing tag while recovering from errors use
+/// their opening tag's span for `close_span`.
+fn finish_innermost(tag_stack: &mut TagStack, close_span: Option<Span>) -> Option<SnaxItem> {
+    let (open_token, children) = tag_stack.pop()?;
+    let close_span = close_span.unwrap_or_else(|| open_token.span());
+    let item = open_token.finish(children, close_span);
+
+    match tag_stack.last_mut() {
+        None => Some(item),
+        Some((_, parent_children)) => {
+            parent_children.push(item);
+            None
+        },
+    }
+}
+
+/// Scans the blocks of an item that was parsed without looking for embedded
+/// markup, stopping at the first error.
+struct Embed<'a, I: Iterator<Item = TokenTree>> {
+    parser: &'a mut Parser<I>,
+    result: Result<(), ParseError>,
+}
+
+impl<I: Iterator<Item = TokenTree>> VisitMut for Embed<'_, I> {
+    fn visit_item_mut(&mut self, item: &mut SnaxItem) {
+        match item {
+            SnaxItem::Content(content) if self.result.is_ok() => {
+                match self.parser.parse_content(content.clone()) {
+                    Ok(parsed) => *item = parsed,
+                    Err(error) => self.result = Err(error),
+                }
+            },
+            _ => visit_mut::visit_item_mut(self, item),
+        }
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut SnaxAttribute) {
+        if self.result.is_ok() {
+            match self.parser.parse_attribute(attribute.clone()) {
+                Ok(parsed) => *attribute = parsed,
+                Err(error) => self.result = Err(error),
+            }
+        }
+    }
+}
+
+/// Where the scanner for embedded markup is within a Rust expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExpressionPosition {
+    /// An expression can start here, so a `<` starts markup.
+    Operand,
+
+    /// An expression just ended, so a `<` is a comparison or generics.
+    Operator,
+
+    /// Inside the parameters of a closure, between `|` and `|`.
+    ClosureParameters,
+}
 
-                assert_eq!(opening_tag.name, closing_tag.name);
+impl ExpressionPosition {
+    /// Moves past `token`, which is followed by `next`.
+    fn after(self, token: &TokenTree, next: Option<&TokenTree>) -> ExpressionPosition {
+        use ExpressionPosition::*;
 
-                let tag = SnaxTag {
-                    name: opening_tag.name,
-                    attributes: opening_tag.attributes,
-                    children,
+        let punct = match token {
+            TokenTree::Punct(punct) => punct,
+            TokenTree::Ident(ident) if self != ClosureParameters => {
+                return if ident == "return" || ident == "move" {
+                    Operand
+                } else {
+                    Operator
                 };
+            },
+            _ if self == ClosureParameters => return ClosureParameters,
+            _ => return Operator,
+        };
 
-                match tag_stack.last_mut() {
-                    None => {
-                        expect_end!(input);
-                        return Ok(SnaxItem::Tag(tag));
-                    },
-                    Some((_, parent_children)) => {
-                        parent_children.push(SnaxItem::Tag(tag));
-                    },
+        match (self, punct.as_char()) {
+            // `||` is two tokens, so an empty parameter list opens and closes
+            // the same way a full one does.
+            (ClosureParameters, '|') => Operand,
+            (ClosureParameters, _) => ClosureParameters,
+            (Operand, '|') => ClosureParameters,
+            (_, ',') | (_, ';') => Operand,
+
+            // `=>` is two tokens, and the second one tells them apart from `=`.
+            (_, '=') if punct.spacing() == Spacing::Joint => match next {
+                Some(next) if is_punct(next, '>') => Operand,
+                _ => Operator,
+            },
+            (_, '=') => Operand,
+            (_, '>') if self == Operand => Operand,
+            _ => Operator,
+        }
+    }
+}
+
+/// Tells whether the `<` at the start of `tokens` opens a qualified path, like
+/// `<T as Default>::default()` or `<Vec<u8>>::new()`, instead of markup. Markup
+/// is never followed by `::`, so the path is found by looking past the
+/// matching `>`.
+fn is_qualified_path(tokens: &[TokenTree]) -> bool {
+    let mut depth = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        let punct = match token {
+            TokenTree::Punct(punct) => punct,
+            _ => continue,
+        };
+
+        // The `>` in `->` doesn't close anything.
+        let after_arrow = match index.checked_sub(1).map(|previous| &tokens[previous]) {
+            Some(TokenTree::Punct(previous)) => previous.as_char() == '-' && previous.spacing() == Spacing::Joint,
+            _ => false,
+        };
+
+        match punct.as_char() {
+            '<' => depth += 1,
+            '>' if after_arrow => {},
+            '>' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return match tokens.get(index + 1..index + 3) {
+                        Some([first, second]) => is_punct(first, ':') && is_punct(second, ':'),
+                        _ => false,
+                    };
                 }
             },
-            HtmlToken::OpenFragment => {
-                tag_stack.push((OpenToken::Fragment, Vec::new()));
+            _ => {},
+        }
+    }
+
+    false
+}
+
+/// Either a token from the tokenizer, or an item that was parsed all at once,
+/// like `@if`.
+enum Parsed {
+    Token(HtmlToken),
+    Item(SnaxItem),
+}
+
+/// Turns tokens from the tokenizer into complete items.
+///
+/// When `recover` is set, errors are collected instead of returned, and the
+/// parser does its best to keep going: bad tokens are skipped up to the next
+/// `<`, stray closing tags are ignored, and tags that are never closed are
+/// closed for the user.
+///
+/// When `embedded` is set, blocks used as content or attribute values are
+/// scanned for markup too.
+struct Parser<I: Iterator<Item = TokenTree>> {
+    input: Peekable<I>,
+    recover: bool,
+    embedded: bool,
+    errors: Vec<ParseError>,
+}
+
+impl<I: Iterator<Item = TokenTree>> Parser<I> {
+    fn new(input: I, recover: bool) -> Self {
+        Parser {
+            input: input.peekable(),
+            recover,
+            embedded: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Creates a parser for the contents of a group, with the same settings as
+    /// this one.
+    fn nested(&self, group: &Group) -> Parser<proc_macro2::token_stream::IntoIter> {
+        self.nested_tokens(group.stream().into_iter())
+    }
+
+    /// Creates a parser for `tokens`, with the same settings as this one.
+    fn nested_tokens<J>(&self, tokens: J) -> Parser<J>
+        where J: Iterator<Item = TokenTree>
+    {
+        let mut parser = Parser::new(tokens, self.recover);
+        parser.embedded = self.embedded;
+        parser
+    }
+
+    fn report(&mut self, error: ParseError) -> Result<(), ParseError> {
+        if self.recover {
+            self.errors.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Skips the rest of a tag that failed partway through its attributes, up
+    /// to and including its `>`. Returns `None` if the input ran out first.
+    fn skip_to_tag_end(&mut self, error: &TokenizeError) -> Option<HtmlTagEnd> {
+        // The token that caused the error may have been the end of the tag.
+        let mut after_slash = match error {
+            TokenizeError::UnexpectedToken { token, .. } if is_punct(token, '>') => {
+                return Some(HtmlTagEnd::Open(token.span()));
             },
-            HtmlToken::CloseFragment => {
-                let (open_token, children) = tag_stack.pop()
-                    .ok_or_else(|| ParseError::UnexpectedItem(HtmlToken::CloseFragment))?;
+            TokenizeError::UnexpectedToken { token, .. } => is_punct(token, '/'),
+            _ => false,
+        };
+
+        for token in self.input.by_ref() {
+            if is_punct(&token, '>') {
+                return Some(if after_slash {
+                    HtmlTagEnd::SelfClosing(token.span())
+                } else {
+                    HtmlTagEnd::Open(token.span())
+                });
+            }
+
+            after_slash = is_punct(&token, '/');
+        }
+
+        None
+    }
+
+    /// Tells whether `error` came from running out of input while tags are
+    /// still open. The tags not being closed is the more useful error, so it's
+    /// reported instead.
+    fn ran_out_inside(&mut self, error: &ParseError, tag_stack: &TagStack) -> bool {
+        match error {
+            ParseError::UnexpectedEnd { .. } => self.input.peek().is_none() && !tag_stack.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn skip_to_next_tag(&mut self) {
+        loop {
+            match self.input.peek() {
+                None => break,
+                Some(TokenTree::Punct(ref punct)) if punct.as_char() == '<' || punct.as_char() == '@' => break,
+                Some(_) => {
+                    self.input.next();
+                },
+            }
+        }
+    }
+
+    /// Parses one complete item, leaving any input after it untouched.
+    ///
+    /// This only returns `Ok(None)` when recovering, if the input ran out
+    /// before anything could be parsed.
+    fn parse_item(&mut self) -> Result<Option<SnaxItem>, ParseError> {
+        let mut tag_stack = TagStack::new();
 
-                match open_token {
-                    OpenToken::Fragment => {},
-                    OpenToken::Tag(_) => return Err(ParseError::UnexpectedItem(HtmlToken::CloseFragment)),
+        loop {
+            if self.input.peek().is_none() && !tag_stack.is_empty() {
+                self.report(unclosed_error(&tag_stack))?;
+
+                loop {
+                    if let Some(item) = finish_innermost(&mut tag_stack, None) {
+                        return Ok(Some(item));
+                    }
                 }
+            }
 
-                let fragment = SnaxFragment {
-                    children,
-                };
+            let parsed = if peek_control_flow(&mut self.input) {
+                self.parse_control_flow().map(Parsed::Item)
+            } else {
+                match parse_html_token(&mut self.input) {
+                    Ok(token) => Ok(Parsed::Token(token)),
+                    Err(TokenizeError::Tag { error, tag }) if self.recover => {
+                        // Keep the tag so that its children and closing tag
+                        // don't cause errors of their own. If the input ran
+                        // out, the open tags are reported on the next pass.
+                        let end = self.skip_to_tag_end(&error);
+                        let error = ParseError::from(*error);
 
-                match tag_stack.last_mut() {
-                    None => {
-                        expect_end!(input);
-                        return Ok(SnaxItem::Fragment(fragment));
-                    },
-                    Some((_, parent_children)) => {
-                        parent_children.push(SnaxItem::Fragment(fragment));
+                        if !self.ran_out_inside(&error, &tag_stack) {
+                            self.report(error)?;
+                        }
+
+                        Ok(Parsed::Token(recovered_tag(*tag, end)))
                     },
+                    Err(error) => Err(ParseError::from(error)),
                 }
+            };
+
+            let token = match parsed {
+                Ok(Parsed::Token(token)) => token,
+                Ok(Parsed::Item(item)) => {
+                    match tag_stack.last_mut() {
+                        None => return Ok(Some(item)),
+                        Some((_, parent_children)) => parent_children.push(item),
+                    }
+
+                    continue;
+                },
+                Err(error) => {
+                    // The open tags are reported at the top of the loop.
+                    if self.ran_out_inside(&error, &tag_stack) {
+                        continue;
+                    }
+
+                    self.report(error)?;
+                    self.skip_to_next_tag();
+
+                    if self.input.peek().is_none() && tag_stack.is_empty() {
+                        return Ok(None);
+                    }
+
+                    continue;
+                },
+            };
+
+            let item = match token {
+                HtmlToken::OpenTag(mut opening_tag) => {
+                    opening_tag.attributes = self.parse_attributes(opening_tag.attributes)?;
+                    tag_stack.push((OpenToken::Tag(opening_tag), Vec::new()));
+                    continue;
+                },
+                HtmlToken::OpenFragment(span) => {
+                    tag_stack.push((OpenToken::Fragment(span), Vec::new()));
+                    continue;
+                },
+                closing @ HtmlToken::CloseTag(_) | closing @ HtmlToken::CloseFragment(_) => {
+                    match self.close(&mut tag_stack, closing)? {
+                        Some(item) => return Ok(Some(item)),
+                        None if self.input.peek().is_none() && tag_stack.is_empty() => return Ok(None),
+                        None => continue,
+                    }
+                },
+                HtmlToken::SelfClosingTag(self_closing_tag) => {
+                    SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+                        name: self_closing_tag.name,
+                        generics: self_closing_tag.generics,
+                        attributes: self.parse_attributes(self_closing_tag.attributes)?,
+                        span: self_closing_tag.span,
+                    })
+                },
+                HtmlToken::Textish(textish) => self.parse_content(textish.content)?,
+            };
+
+            match tag_stack.last_mut() {
+                None => return Ok(Some(item)),
+                Some((_, parent_children)) => parent_children.push(item),
+            }
+        }
+    }
+
+    /// Parses a control-flow item starting at `@`, along with everything in
+    /// its branches.
+    fn parse_control_flow(&mut self) -> Result<SnaxItem, ParseError> {
+        let (at, keyword) = parse_control_keyword(&mut self.input)?;
+        let start = at.span();
+
+        if keyword == "if" {
+            return Ok(SnaxItem::If(self.parse_if(start)?));
+        }
+
+        if keyword == "for" {
+            let (pattern, _) = parse_for_pattern(&mut self.input)?;
+            let (iterator, body) = parse_block_head(
+                &mut self.input,
+                "an iterator after `in`",
+                "a block for the body of `@for`",
+            )?;
+
+            let span = join_spans(start, body.span());
+
+            return Ok(SnaxItem::For(SnaxFor {
+                pattern: pattern.into_iter().collect(),
+                iterator: iterator.into_iter().collect(),
+                body: self.parse_children(body)?,
+                span,
+            }));
+        }
+
+        let (expression, arms) = parse_block_head(
+            &mut self.input,
+            "an expression after `@match`",
+            "a block of arms for `@match`",
+        )?;
+
+        let span = join_spans(start, arms.span());
+
+        Ok(SnaxItem::Match(SnaxMatch {
+            expression: expression.into_iter().collect(),
+            arms: self.parse_match_arms(arms)?,
+            span,
+        }))
+    }
+
+    /// Parses the rest of an `@if` or `else if`, starting after `if`.
+    fn parse_if(&mut self, start: Span) -> Result<SnaxIf, ParseError> {
+        let (condition, then_block) = parse_if_condition(&mut self.input)?;
+
+        let mut end = then_block.span();
+        let then_branch = self.parse_children(then_block)?;
+
+        let else_branch = match parse_else(&mut self.input) {
+            None => None,
+            Some(_) => match parse_else_if(&mut self.input) {
+                Some(if_token) => {
+                    let nested = self.parse_if(if_token.span())?;
+                    end = nested.span;
+
+                    Some(vec![SnaxItem::If(nested)])
+                },
+                None => {
+                    let else_block = parse_block(&mut self.input, "`if` or a block after `else`")?;
+                    end = else_block.span();
+
+                    Some(self.parse_children(else_block)?)
+                },
             },
-            HtmlToken::SelfClosingTag(self_closing_tag) => {
-                let tag = SnaxSelfClosingTag {
-                    name: self_closing_tag.name,
-                    attributes: self_closing_tag.attributes,
-                };
+        };
 
-                match tag_stack.last_mut() {
-                    None => {
-                        expect_end!(input);
-                        return Ok(SnaxItem::SelfClosingTag(tag));
-                    },
-                    Some((_, parent_children)) => {
-                        parent_children.push(SnaxItem::SelfClosingTag(tag));
-                    },
+        Ok(SnaxIf {
+            condition: condition.into_iter().collect(),
+            then_branch,
+            else_branch,
+            span: join_spans(start, end),
+        })
+    }
+
+    /// Parses the arms inside the block of a `@match`.
+    ///
+    /// Like in Rust, arms need to be separated by commas unless their body is
+    /// a block.
+    fn parse_match_arms(&mut self, block: Group) -> Result<Vec<SnaxMatchArm>, ParseError> {
+        let mut parser = self.nested(&block);
+        let mut arms = Vec::new();
+
+        while parser.input.peek().is_some() {
+            let head = match parse_match_arm_head(&mut parser.input) {
+                Ok(head) => head,
+                Err(error) => {
+                    parser.report(error.into())?;
+                    break;
+                },
+            };
+
+            let (body, needs_comma) = match parse_optional_block(&mut parser.input) {
+                Some(body) => (parser.parse_children(body)?, false),
+                None => (parser.parse_item()?.into_iter().collect(), true),
+            };
+
+            arms.push(SnaxMatchArm {
+                pattern: head.pattern.into_iter().collect(),
+                guard: head.guard.map(|(_, guard)| guard.into_iter().collect()),
+                body,
+            });
+
+            if parse_comma(&mut parser.input).is_none() && needs_comma {
+                if let Some(token) = parser.input.peek().cloned() {
+                    parser.report(ParseError::UnexpectedToken {
+                        token,
+                        expected: "`,` after the match arm",
+                    })?;
+                }
+            }
+        }
+
+        self.errors.extend(parser.errors);
+        Ok(arms)
+    }
+
+    /// Parses the contents of a block as a list of items.
+    fn parse_children(&mut self, block: Group) -> Result<Vec<SnaxItem>, ParseError> {
+        let mut parser = self.nested(&block);
+        let mut items = Vec::new();
+
+        while parser.input.peek().is_some() {
+            items.extend(parser.parse_item()?);
+        }
+
+        self.errors.extend(parser.errors);
+        Ok(items)
+    }
+
+    /// Turns a block used as content into an expression if it has markup
+    /// embedded in it.
+    fn parse_content(&mut self, content: TokenTree) -> Result<SnaxItem, ParseError> {
+        if let TokenTree::Group(group) = &content {
+            if let Some(expression) = self.parse_expression(group)? {
+                return Ok(SnaxItem::Expression(expression));
+            }
+        }
+
+        Ok(SnaxItem::Content(content))
+    }
+
+    /// Turns blocks used as attribute values into expressions if they have
+    /// markup embedded in them.
+    fn parse_attributes(&mut self, attributes: Vec<SnaxAttribute>) -> Result<Vec<SnaxAttribute>, ParseError> {
+        attributes.into_iter()
+            .map(|attribute| self.parse_attribute(attribute))
+            .collect()
+    }
+
+    /// Looks for embedded markup in one attribute, like `parse_attributes`.
+    fn parse_attribute(&mut self, attribute: SnaxAttribute) -> Result<SnaxAttribute, ParseError> {
+        match attribute {
+            SnaxAttribute::Simple { name, value: AttributeValue::Block(group) } => {
+                match self.parse_expression(&group)? {
+                    Some(value) => Ok(SnaxAttribute::Expression { name, value }),
+                    None => Ok(SnaxAttribute::Simple { name, value: AttributeValue::Block(group) }),
                 }
             },
-            HtmlToken::Textish(textish) => {
-                match tag_stack.last_mut() {
-                    None => {
-                        expect_end!(input);
-                        return Ok(SnaxItem::Content(textish.content));
-                    },
-                    Some((_, parent_children)) => {
-                        parent_children.push(SnaxItem::Content(textish.content));
+            SnaxAttribute::Markup { name, mut value } if self.embedded => {
+                // The tokenizer parses markup in attribute values on its own,
+                // so its blocks still need to be scanned.
+                let mut embed = Embed {
+                    parser: self,
+                    result: Ok(()),
+                };
+
+                embed.visit_item_mut(&mut value);
+                embed.result?;
+
+                Ok(SnaxAttribute::Markup { name, value })
+            },
+            other => Ok(other),
+        }
+    }
+
+    /// Scans a group for markup when embedded markup is enabled, returning
+    /// `None` if there isn't any so that the group can be kept as it was.
+    ///
+    /// Markup can only start with a `<` in a place where an expression is
+    /// expected, like after `=>` or at the start of a group, so comparisons
+    /// like `a < b` are left alone. Qualified paths like `<T>::new()` are left
+    /// alone too.
+    fn parse_expression(&mut self, group: &Group) -> Result<Option<SnaxExpression>, ParseError> {
+        if !self.embedded {
+            return Ok(None);
+        }
+
+        // The tokens are kept around so that the parser's position in them can
+        // be used to look ahead without copying what's left of the input.
+        let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+        let mut parser = self.nested_tokens(tokens.iter().cloned());
+        let mut parts = Vec::new();
+        let mut found_markup = false;
+        let mut position = ExpressionPosition::Operand;
+
+        loop {
+            let index = tokens.len() - parser.input.len();
+            let token = match tokens.get(index) {
+                None => break,
+                Some(token) => token,
+            };
+
+            if position == ExpressionPosition::Operand && is_punct(token, '<') && !is_qualified_path(&tokens[index..]) {
+                match parser.parse_item()? {
+                    Some(item) => parts.push(SnaxExpressionPart::Item(item)),
+                    None => break,
+                }
+
+                found_markup = true;
+                position = ExpressionPosition::Operator;
+                continue;
+            }
+
+            let token = parser.input.next().unwrap();
+            position = position.after(&token, parser.input.peek());
+
+            match token {
+                TokenTree::Group(inner) => match parser.parse_expression(&inner)? {
+                    Some(expression) => {
+                        found_markup = true;
+                        parts.push(SnaxExpressionPart::Group(expression));
                     },
+                    None => parts.push(SnaxExpressionPart::Token(TokenTree::Group(inner))),
+                },
+                other => parts.push(SnaxExpressionPart::Token(other)),
+            }
+        }
+
+        self.errors.extend(parser.errors);
+
+        if !found_markup {
+            return Ok(None);
+        }
+
+        Ok(Some(SnaxExpression {
+            delimiter: group.delimiter(),
+            parts,
+            span: group.span(),
+        }))
+    }
+
+    /// Closes the innermost open tag or fragment, returning the finished item
+    /// if it was the root.
+    ///
+    /// When recovering from a closing tag that doesn't match, any tags inside
+    /// of the one it does match are closed too. If it doesn't match anything,
+    /// it's ignored.
+    fn close(&mut self, tag_stack: &mut TagStack, closing: HtmlToken) -> Result<Option<SnaxItem>, ParseError> {
+        let top_matches = match tag_stack.last() {
+            Some((open_token, _)) => open_token.is_closed_by(&closing),
+            None => false,
+        };
+
+        if top_matches {
+            return Ok(finish_innermost(tag_stack, Some(closing.span())));
+        }
+
+        let matching_index = tag_stack.iter()
+            .rposition(|(open_token, _)| open_token.is_closed_by(&closing));
+
+        let close_span = closing.span();
+
+        let opening = tag_stack.last().map(|(open_token, _)| open_token);
+        self.report(close_error(opening, closing))?;
+
+        match matching_index {
+            Some(index) => {
+                while tag_stack.len() > index + 1 {
+                    finish_innermost(tag_stack, None);
                 }
+
+                Ok(finish_innermost(tag_stack, Some(close_span)))
             },
+            None => Ok(None),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Turns a tag that failed partway through its attributes back into a token.
+/// Tags cut off by the end of the input are treated as self-closing, since
+/// nothing can follow them.
+fn recovered_tag(tag: HtmlPartialTag, end: Option<HtmlTagEnd>) -> HtmlToken {
+    match end {
+        Some(HtmlTagEnd::Open(end)) => HtmlToken::OpenTag(HtmlOpenToken {
+            name: tag.name,
+            generics: tag.generics,
+            attributes: tag.attributes,
+            span: join_spans(tag.start, end),
+        }),
+        Some(HtmlTagEnd::SelfClosing(end)) => HtmlToken::SelfClosingTag(HtmlSelfClosingToken {
+            name: tag.name,
+            generics: tag.generics,
+            attributes: tag.attributes,
+            span: join_spans(tag.start, end),
+        }),
+        None => HtmlToken::SelfClosingTag(HtmlSelfClosingToken {
+            name: tag.name,
+            generics: tag.generics,
+            attributes: tag.attributes,
+            span: tag.start,
+        }),
+    }
+}
+
+/// Parses an element or fragment used as an attribute value, leaving the tokens
+/// after it for the rest of the tag.
+pub(crate) fn parse_attribute_markup<I>(input: &mut Peekable<I>) -> Result<SnaxItem, ParseError>
+    where I: Iterator<Item = TokenTree>
+{
+    // The parser never looks past the end of an item that it parses
+    // successfully, so no tokens are lost when it's dropped. The input is
+    // type-erased so that nested attribute markup doesn't need a new parser
+    // type for every level.
+    let input: &mut dyn Iterator<Item = TokenTree> = input;
+    let mut parser = Parser::new(input, false);
+
+    let item = parser.parse_item()?
+        .expect("parse_item only returns None when recovering");
+
+    Ok(item)
+}
+
+/// Attempts to parse a `proc_macro2::TokenStream` into a `SnaxItem`.
+pub fn parse(input_stream: TokenStream) -> Result<SnaxItem, ParseError> {
+    let mut parser = Parser::new(input_stream.into_iter(), false);
+    let item = parser.parse_item()?
+        .expect("parse_item only returns None when recovering");
+
+    expect_end!(parser.input);
+
+    Ok(item)
+}
+
+/// Like [`parse`], but also parses markup embedded in the Rust expressions
+/// used as content and attribute values:
+///
+/// ```html
+/// <ul>{ users.iter().map(|user| <li>{ &user.name }</li>) }</ul>
+/// ```
+///
+/// Blocks containing markup become [`SnaxItem::Expression`] and
+/// [`SnaxAttribute::Expression`], and blocks without any are left as they
+/// were. Markup is recognized by a `<` where an expression can start: at the
+/// start of a group, after `,`, `;`, `=`, `=>`, `return`, or a closure's
+/// parameters. A `<` anywhere else is treated as Rust code, like the one in
+/// `a < b`, and so is one that starts a qualified path, like
+/// `<T as Default>::default()` or `<Vec<u8>>::new()`.
+///
+/// [`parse`]: fn.parse.html
+/// [`SnaxItem::Expression`]: enum.SnaxItem.html#variant.Expression
+/// [`SnaxAttribute::Expression`]: enum.SnaxAttribute.html#variant.Expression
+pub fn parse_embedded(input_stream: TokenStream) -> Result<SnaxItem, ParseError> {
+    let mut parser = Parser::new(input_stream.into_iter(), false);
+    parser.embedded = true;
+
+    let item = parser.parse_item()?
+        .expect("parse_item only returns None when recovering");
+
+    expect_end!(parser.input);
+
+    Ok(item)
+}
+
+/// Attempts to parse a `proc_macro2::TokenStream` containing any number of
+/// items one after another, like a list of children without a fragment around
+/// them:
+///
+/// ```html
+/// <li>"One"</li>
+/// <li>"Two"</li>
+/// ```
+pub fn parse_many(input_stream: TokenStream) -> Result<Vec<SnaxItem>, ParseError> {
+    let mut parser = Parser::new(input_stream.into_iter(), false);
+    let mut items = Vec::new();
+
+    while parser.input.peek().is_some() {
+        let item = parser.parse_item()?
+            .expect("parse_item only returns None when recovering");
+
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+/// Attempts to parse a single `SnaxItem` from the start of a
+/// `proc_macro2::TokenStream`, returning it along with the rest of the tokens.
+///
+/// This is useful for macros that accept markup alongside other arguments:
+///
+/// ```html
+/// <App /> => target
+///            ^^^^^^ returned untouched
+/// ```
+pub fn parse_prefix(input_stream: TokenStream) -> Result<(SnaxItem, TokenStream), ParseError> {
+    let mut parser = Parser::new(input_stream.into_iter(), false);
+    let item = parser.parse_item()?
+        .expect("parse_item only returns None when recovering");
+
+    Ok((item, parser.input.collect()))
+}
+
+/// Parses a `proc_macro2::TokenStream` into a `SnaxItem`, recovering from
+/// errors instead of stopping at the first one.
+///
+/// Every error encountered is returned, which lets macros report all of the
+/// problems with their input at once. The item is as much of the tree as could
+/// be salvaged, and is only `None` if nothing could be parsed at all.
+pub fn parse_recovering(input_stream: TokenStream) -> (Option<SnaxItem>, Vec<ParseError>) {
+    let mut parser = Parser::new(input_stream.into_iter(), true);
+
+    // Errors are never returned while recovering.
+    let item = parser.parse_item().unwrap_or(None);
+
+    if let Some(token) = parser.input.next() {
+        parser.errors.push(ParseError::UnexpectedToken {
+            token,
+            expected: "the end of the input",
+        });
+    }
+
+    (item, parser.errors)
+}
@@ -0,0 +1,119 @@
+//! Helpers for reading the values of Rust literals.
+
+use proc_macro2::Literal;
+
+/// Returns the text a literal stands for. String and character literals are
+/// unquoted and unescaped, and numbers are written the way they'd be displayed,
+/// like `1000` for `1_000` and `5` for `5u8`. Returns `None` for byte strings
+/// and malformed literals.
+pub(crate) fn literal_text(literal: &Literal) -> Option<String> {
+    let source = literal.to_string();
+
+    if source.starts_with('b') || source.starts_with("c\"") || source.starts_with("cr") {
+        return None;
+    }
+
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = &raw[hashes..];
+
+        return body.strip_prefix('"')?
+            .strip_suffix(&format!("\"{}", "#".repeat(hashes)))
+            .map(str::to_string);
+    }
+
+    if source.starts_with('"') {
+        let end = source.rfind('"')?;
+        return unescape(&source[1..end]);
+    }
+
+    if source.starts_with('\'') {
+        let end = source.rfind('\'')?;
+        return unescape(&source[1..end]);
+    }
+
+    if source.starts_with(|c: char| c.is_ascii_digit()) {
+        return number_text(&source);
+    }
+
+    Some(source)
+}
+
+/// Removes digit separators and the type suffix from a number literal.
+/// Integers are parsed so that ones written in hex, octal, or binary come out
+/// in decimal, the same as they would at runtime.
+fn number_text(source: &str) -> Option<String> {
+    let digits: String = source.chars().filter(|&c| c != '_').collect();
+
+    let (radix, body) = match digits.get(..2) {
+        Some("0x") => (16, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        _ => (10, digits.as_str()),
+    };
+
+    // `f` is a hex digit, but hex literals can't have a float suffix anyway.
+    let suffixes: &[char] = if radix == 16 { &['u', 'i'] } else { &['u', 'i', 'f'] };
+    let body = match body.find(suffixes) {
+        Some(index) => &body[..index],
+        None => body,
+    };
+
+    if radix == 10 && body.contains(|c: char| !c.is_ascii_digit()) {
+        return Some(body.to_string());
+    }
+
+    u128::from_str_radix(body, radix).ok().map(|value| value.to_string())
+}
+
+/// Resolves the escape sequences in the body of a string or char literal.
+fn unescape(body: &str) -> Option<String> {
+    let mut output = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => output.push('\n'),
+            'r' => output.push('\r'),
+            't' => output.push('\t'),
+            '0' => output.push('\0'),
+            '\\' => output.push('\\'),
+            '\'' => output.push('\''),
+            '"' => output.push('"'),
+            'x' => {
+                let digits: String = chars.by_ref().take(2).collect();
+                let value = u8::from_str_radix(&digits, 16).ok()?;
+                output.push(value as char);
+            },
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+
+                let digits: String = chars.by_ref()
+                    .take_while(|&c| c != '}')
+                    .filter(|&c| c != '_')
+                    .collect();
+                let value = u32::from_str_radix(&digits, 16).ok()?;
+                output.push(std::char::from_u32(value)?);
+            },
+            '\n' | '\r' => {
+                while let Some(c) = chars.peek() {
+                    if !c.is_whitespace() {
+                        break;
+                    }
+
+                    chars.next();
+                }
+            },
+            _ => return None,
+        }
+    }
+
+    Some(output)
+}
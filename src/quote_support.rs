@@ -0,0 +1,277 @@
+//! Implementations of `quote::ToTokens` for snax types, enabled with the
+//! `quote` feature.
+//!
+//! Names and values keep their original spans. Individual punctuation tokens
+//! aren't kept around after parsing, so they borrow the span of the tag or
+//! attribute that they're part of.
+
+use proc_macro2::{
+    Delimiter,
+    Group,
+    Ident,
+    Punct,
+    Spacing,
+    Span,
+    TokenStream,
+};
+use quote::{ToTokens, TokenStreamExt};
+
+use crate::{
+    AttributeValue,
+    SnaxAttribute,
+    SnaxExpression,
+    SnaxExpressionPart,
+    SnaxFor,
+    SnaxFragment,
+    SnaxIf,
+    SnaxItem,
+    SnaxMatch,
+    SnaxName,
+    SnaxPath,
+    SnaxSelfClosingTag,
+    SnaxTag,
+};
+
+fn punct(tokens: &mut TokenStream, character: char, spacing: Spacing, span: Span) {
+    let mut punct = Punct::new(character, spacing);
+    punct.set_span(span);
+    tokens.append(punct);
+}
+
+fn path_separator(tokens: &mut TokenStream, span: Span) {
+    punct(tokens, ':', Spacing::Joint, span);
+    punct(tokens, ':', Spacing::Alone, span);
+}
+
+fn keyword(tokens: &mut TokenStream, keyword: &str, span: Span) {
+    tokens.append(Ident::new(keyword, span));
+}
+
+/// Writes a list of items inside of braces.
+fn block(tokens: &mut TokenStream, items: &[SnaxItem], span: Span) {
+    let mut inner = TokenStream::new();
+    inner.append_all(items);
+
+    let mut group = Group::new(Delimiter::Brace, inner);
+    group.set_span(span);
+    tokens.append(group);
+}
+
+/// Writes a tag's name followed by its turbofish generic arguments, if any.
+fn tag_name(tokens: &mut TokenStream, name: &SnaxPath, generics: &Option<TokenStream>) {
+    name.to_tokens(tokens);
+
+    if let Some(generics) = generics {
+        let span = name.span();
+
+        path_separator(tokens, span);
+        punct(tokens, '<', Spacing::Alone, span);
+        tokens.extend(generics.clone());
+        punct(tokens, '>', Spacing::Alone, span);
+    }
+}
+
+impl ToTokens for SnaxName {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.first.to_tokens(tokens);
+
+        for (separator, ident) in &self.rest {
+            punct(tokens, separator.as_char(), Spacing::Alone, ident.span());
+            ident.to_tokens(tokens);
+        }
+    }
+}
+
+impl ToTokens for SnaxPath {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.first.to_tokens(tokens);
+
+        for segment in &self.rest {
+            path_separator(tokens, segment.span());
+            segment.to_tokens(tokens);
+        }
+    }
+}
+
+impl ToTokens for AttributeValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(self.tokens());
+    }
+}
+
+impl ToTokens for SnaxAttribute {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            SnaxAttribute::Simple { name, value } => {
+                name.to_tokens(tokens);
+                punct(tokens, '=', Spacing::Alone, name.span());
+                value.to_tokens(tokens);
+            },
+            SnaxAttribute::Flag { name } => {
+                name.to_tokens(tokens);
+            },
+            SnaxAttribute::Spread { value, span } => {
+                let span = *span;
+
+                let mut inner = TokenStream::new();
+                punct(&mut inner, '.', Spacing::Joint, span);
+                punct(&mut inner, '.', Spacing::Alone, span);
+                inner.extend(value.clone());
+
+                let mut group = Group::new(Delimiter::Brace, inner);
+                group.set_span(span);
+                tokens.append(group);
+            },
+            SnaxAttribute::Markup { name, value } => {
+                name.to_tokens(tokens);
+                punct(tokens, '=', Spacing::Alone, name.span());
+                value.to_tokens(tokens);
+            },
+            SnaxAttribute::Expression { name, value } => {
+                name.to_tokens(tokens);
+                punct(tokens, '=', Spacing::Alone, name.span());
+                value.to_tokens(tokens);
+            },
+        }
+    }
+}
+
+impl ToTokens for SnaxExpression {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut inner = TokenStream::new();
+
+        for part in &self.parts {
+            match part {
+                SnaxExpressionPart::Token(token) => token.to_tokens(&mut inner),
+                SnaxExpressionPart::Group(group) => group.to_tokens(&mut inner),
+                SnaxExpressionPart::Item(item) => item.to_tokens(&mut inner),
+            }
+        }
+
+        let mut group = Group::new(self.delimiter, inner);
+        group.set_span(self.span);
+        tokens.append(group);
+    }
+}
+
+impl ToTokens for SnaxTag {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        punct(tokens, '<', Spacing::Alone, self.open_span);
+        tag_name(tokens, &self.name, &self.generics);
+        tokens.append_all(&self.attributes);
+        punct(tokens, '>', Spacing::Alone, self.open_span);
+
+        tokens.append_all(&self.children);
+
+        punct(tokens, '<', Spacing::Joint, self.close_span);
+        punct(tokens, '/', Spacing::Alone, self.close_span);
+        self.name.to_tokens(tokens);
+        punct(tokens, '>', Spacing::Alone, self.close_span);
+    }
+}
+
+impl ToTokens for SnaxSelfClosingTag {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        punct(tokens, '<', Spacing::Alone, self.span);
+        tag_name(tokens, &self.name, &self.generics);
+        tokens.append_all(&self.attributes);
+        punct(tokens, '/', Spacing::Joint, self.span);
+        punct(tokens, '>', Spacing::Alone, self.span);
+    }
+}
+
+impl ToTokens for SnaxFragment {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        punct(tokens, '<', Spacing::Joint, self.open_span);
+        punct(tokens, '>', Spacing::Alone, self.open_span);
+
+        tokens.append_all(&self.children);
+
+        punct(tokens, '<', Spacing::Joint, self.close_span);
+        punct(tokens, '/', Spacing::Joint, self.close_span);
+        punct(tokens, '>', Spacing::Alone, self.close_span);
+    }
+}
+
+impl ToTokens for SnaxIf {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        punct(tokens, '@', Spacing::Alone, self.span);
+        self.write_branches(tokens);
+    }
+}
+
+impl SnaxIf {
+    /// Writes everything after the `@`, which is also how an `else if` is
+    /// written.
+    fn write_branches(&self, tokens: &mut TokenStream) {
+        keyword(tokens, "if", self.span);
+        tokens.extend(self.condition.clone());
+        block(tokens, &self.then_branch, self.span);
+
+        match self.else_branch.as_deref() {
+            None => {},
+            Some([SnaxItem::If(else_if)]) => {
+                keyword(tokens, "else", self.span);
+                else_if.write_branches(tokens);
+            },
+            Some(else_branch) => {
+                keyword(tokens, "else", self.span);
+                block(tokens, else_branch, self.span);
+            },
+        }
+    }
+}
+
+impl ToTokens for SnaxFor {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        punct(tokens, '@', Spacing::Alone, self.span);
+        keyword(tokens, "for", self.span);
+        tokens.extend(self.pattern.clone());
+        keyword(tokens, "in", self.span);
+        tokens.extend(self.iterator.clone());
+        block(tokens, &self.body, self.span);
+    }
+}
+
+impl ToTokens for SnaxMatch {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        punct(tokens, '@', Spacing::Alone, self.span);
+        keyword(tokens, "match", self.span);
+        tokens.extend(self.expression.clone());
+
+        let mut arms = TokenStream::new();
+
+        for arm in &self.arms {
+            arms.extend(arm.pattern.clone());
+
+            if let Some(guard) = &arm.guard {
+                keyword(&mut arms, "if", self.span);
+                arms.extend(guard.clone());
+            }
+
+            punct(&mut arms, '=', Spacing::Joint, self.span);
+            punct(&mut arms, '>', Spacing::Alone, self.span);
+            block(&mut arms, &arm.body, self.span);
+        }
+
+        let mut group = Group::new(Delimiter::Brace, arms);
+        group.set_span(self.span);
+        tokens.append(group);
+    }
+}
+
+impl ToTokens for SnaxItem {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            SnaxItem::Tag(tag) => tag.to_tokens(tokens),
+            SnaxItem::SelfClosingTag(tag) => tag.to_tokens(tokens),
+            SnaxItem::Fragment(fragment) => fragment.to_tokens(tokens),
+            SnaxItem::Content(content) => content.to_tokens(tokens),
+            SnaxItem::Expression(expression) => expression.to_tokens(tokens),
+            SnaxItem::If(node) => node.to_tokens(tokens),
+            SnaxItem::For(node) => node.to_tokens(tokens),
+            SnaxItem::Match(node) => node.to_tokens(tokens),
+        }
+    }
+}
+
@@ -0,0 +1,474 @@
+//! A small subset of CSS selectors for querying a parsed tree.
+
+use std::{
+    fmt,
+    iter::Peekable,
+    str::CharIndices,
+};
+
+use crate::{
+    SnaxAttribute,
+    SnaxItem,
+};
+
+/// Finds every tag in `item`, including `item` itself, that matches a CSS
+/// selector. Matches are returned in document order.
+///
+/// The supported syntax is:
+///
+/// - Tag names, like `li` or `ui::Button`, and the universal selector `*`
+/// - Attribute presence, like `[key]`, which matches both valued attributes
+///   and flags
+/// - Attribute equality, like `[type="submit"]` or `[tabindex=1]`, which
+///   compares against literal attribute values
+/// - The `:first-child` pseudo-class
+/// - The descendant (` `) and child (`>`) combinators
+///
+/// Fragments and control flow are transparent: their children are treated as
+/// children of their parent tag. Content is ignored when working out which tag
+/// is a first child, and the first tag at the root counts as one.
+///
+/// ```
+/// use quote::quote;
+///
+/// let item = snax::parse(quote!(
+///     <ul>
+///         <li key="a">"A"</li>
+///         <li>"B"</li>
+///     </ul>
+/// )).unwrap();
+///
+/// assert_eq!(snax::select(&item, "ul > li[key]").unwrap().len(), 1);
+/// ```
+pub fn select<'a>(item: &'a SnaxItem, selector: &str) -> Result<Vec<&'a SnaxItem>, SelectorError> {
+    let selector = parse_selector(selector)?;
+
+    let mut elements = Vec::new();
+    collect_elements(item, None, true, &mut elements);
+
+    let matches = (0..elements.len())
+        .filter(|&index| selector.matches(&elements, index, selector.compounds.len() - 1))
+        .map(|index| elements[index].item)
+        .collect();
+
+    Ok(matches)
+}
+
+/// An error encountered while parsing a selector given to [`select`].
+///
+/// [`select`]: fn.select.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorError {
+    /// The selector ended early.
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+
+    /// The selector contained a character that wasn't expected at the given
+    /// byte offset.
+    UnexpectedChar {
+        found: char,
+        position: usize,
+        expected: &'static str,
+    },
+
+    /// The selector used a pseudo-class other than `:first-child`.
+    UnsupportedPseudoClass(String),
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelectorError::UnexpectedEnd { expected } => {
+                write!(formatter, "unexpected end of selector, expected {}", expected)
+            },
+            SelectorError::UnexpectedChar { found, position, expected } => {
+                write!(formatter, "unexpected `{}` at position {} in selector, expected {}", found, position, expected)
+            },
+            SelectorError::UnsupportedPseudoClass(name) => {
+                write!(formatter, "unsupported pseudo-class `:{}`", name)
+            },
+        }
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+#[derive(Debug)]
+struct Selector {
+    /// Compound selectors from left to right. Each one after the first is
+    /// paired with the combinator joining it to the one before it.
+    compounds: Vec<(Combinator, Compound)>,
+}
+
+impl Selector {
+    fn matches(&self, elements: &[Element], index: usize, position: usize) -> bool {
+        let (combinator, compound) = &self.compounds[position];
+
+        if !compound.matches(&elements[index]) {
+            return false;
+        }
+
+        if position == 0 {
+            return true;
+        }
+
+        match combinator {
+            Combinator::Child => match elements[index].parent {
+                Some(parent) => self.matches(elements, parent, position - 1),
+                None => false,
+            },
+            Combinator::Descendant => {
+                let mut ancestor = elements[index].parent;
+
+                while let Some(current) = ancestor {
+                    if self.matches(elements, current, position - 1) {
+                        return true;
+                    }
+
+                    ancestor = elements[current].parent;
+                }
+
+                false
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Default)]
+struct Compound {
+    /// The tag name to match, or `None` for `*`.
+    name: Option<String>,
+    attributes: Vec<AttributeSelector>,
+    first_child: bool,
+}
+
+impl Compound {
+    fn matches(&self, element: &Element) -> bool {
+        let (name, attributes) = match element.item {
+            SnaxItem::Tag(tag) => (&tag.name, &tag.attributes),
+            SnaxItem::SelfClosingTag(tag) => (&tag.name, &tag.attributes),
+            _ => return false,
+        };
+
+        if let Some(expected) = &self.name {
+            if name.to_string() != *expected {
+                return false;
+            }
+        }
+
+        if self.first_child && !element.first_child {
+            return false;
+        }
+
+        self.attributes.iter()
+            .all(|selector| attributes.iter().any(|attribute| selector.matches(attribute)))
+    }
+}
+
+#[derive(Debug)]
+struct AttributeSelector {
+    name: String,
+    value: Option<String>,
+}
+
+impl AttributeSelector {
+    fn matches(&self, attribute: &SnaxAttribute) -> bool {
+        match attribute.name() {
+            Some(name) if name.to_string() == self.name => {},
+            _ => return false,
+        }
+
+        let expected = match &self.value {
+            Some(expected) => expected,
+            None => return true,
+        };
+
+        match attribute {
+            SnaxAttribute::Simple { value, .. } => {
+                value.literal_text().as_ref() == Some(expected)
+            },
+            _ => false,
+        }
+    }
+}
+
+/// A tag in the tree, flattened out of its fragments and control flow.
+struct Element<'a> {
+    item: &'a SnaxItem,
+    parent: Option<usize>,
+    first_child: bool,
+}
+
+/// Adds every tag in `item` to `elements` in document order, looking through
+/// fragments and control flow. `first` tells whether nothing before `item`
+/// could be its parent's first child. Returns the same for the next sibling.
+fn collect_elements<'a>(item: &'a SnaxItem, parent: Option<usize>, first: bool, elements: &mut Vec<Element<'a>>) -> bool {
+    match item {
+        SnaxItem::Tag(_) | SnaxItem::SelfClosingTag(_) => {
+            let index = elements.len();
+            elements.push(Element {
+                item,
+                parent,
+                first_child: first,
+            });
+
+            let mut child_first = true;
+            for child in item.children() {
+                child_first = collect_elements(child, Some(index), child_first, elements);
+            }
+
+            false
+        },
+        SnaxItem::Content(_) | SnaxItem::Expression(_) => first,
+        _ => {
+            // Only one branch of a control-flow item is used at a time, so the
+            // first tag in each of them could be a first child.
+            let mut next_first = first;
+
+            for body in item.bodies() {
+                let mut body_first = first;
+                for child in body {
+                    body_first = collect_elements(child, parent, body_first, elements);
+                }
+
+                next_first &= body_first;
+            }
+
+            next_first
+        },
+    }
+}
+
+struct SelectorParser<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+fn parse_selector(source: &str) -> Result<Selector, SelectorError> {
+    let mut parser = SelectorParser {
+        source,
+        chars: source.char_indices().peekable(),
+    };
+
+    parser.skip_whitespace();
+
+    let mut compounds = vec![(Combinator::Descendant, parser.parse_compound()?)];
+
+    loop {
+        let had_whitespace = parser.skip_whitespace();
+
+        let combinator = match parser.chars.peek() {
+            None => break,
+            Some((_, '>')) => {
+                parser.chars.next();
+                parser.skip_whitespace();
+                Combinator::Child
+            },
+            Some(&(position, found)) => {
+                if !had_whitespace {
+                    return Err(SelectorError::UnexpectedChar {
+                        found,
+                        position,
+                        expected: "a combinator",
+                    });
+                }
+
+                Combinator::Descendant
+            },
+        };
+
+        compounds.push((combinator, parser.parse_compound()?));
+    }
+
+    Ok(Selector { compounds })
+}
+
+impl<'a> SelectorParser<'a> {
+    /// Skips whitespace, returning whether there was any.
+    fn skip_whitespace(&mut self) -> bool {
+        let mut skipped = false;
+
+        while let Some((_, c)) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+
+            self.chars.next();
+            skipped = true;
+        }
+
+        skipped
+    }
+
+    fn unexpected(&mut self, expected: &'static str) -> SelectorError {
+        match self.chars.peek() {
+            Some(&(position, found)) => SelectorError::UnexpectedChar { found, position, expected },
+            None => SelectorError::UnexpectedEnd { expected },
+        }
+    }
+
+    fn expect(&mut self, wanted: char, expected: &'static str) -> Result<(), SelectorError> {
+        match self.chars.peek() {
+            Some(&(_, c)) if c == wanted => {
+                self.chars.next();
+                Ok(())
+            },
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    /// Reads a name made of identifier characters and dashes, along with any
+    /// extra characters accepted by `extra`.
+    fn parse_word(&mut self, extra: impl Fn(char) -> bool) -> &'a str {
+        let start = match self.chars.peek() {
+            Some(&(position, _)) => position,
+            None => return "",
+        };
+        let mut end = start;
+
+        while let Some(&(position, c)) = self.chars.peek() {
+            if !(c.is_alphanumeric() || c == '_' || c == '-' || extra(c)) {
+                break;
+            }
+
+            self.chars.next();
+            end = position + c.len_utf8();
+        }
+
+        &self.source[start..end]
+    }
+
+    /// Reads a tag name, which can be a path like `ui::Button`.
+    fn parse_tag_name(&mut self) -> Result<String, SelectorError> {
+        let mut name = self.parse_word(|_| false).to_string();
+
+        loop {
+            let mut lookahead = self.chars.clone();
+            match (lookahead.next(), lookahead.next()) {
+                (Some((_, ':')), Some((_, ':'))) => {},
+                _ => break,
+            }
+
+            self.chars.next();
+            self.chars.next();
+
+            let segment = self.parse_word(|_| false);
+            if segment.is_empty() {
+                return Err(self.unexpected("a name after `::`"));
+            }
+
+            name.push_str("::");
+            name.push_str(segment);
+        }
+
+        Ok(name)
+    }
+
+    fn parse_compound(&mut self) -> Result<Compound, SelectorError> {
+        let mut compound = Compound::default();
+        let mut empty = true;
+
+        if let Some((_, '*')) = self.chars.peek() {
+            self.chars.next();
+            empty = false;
+        } else {
+            let name = self.parse_tag_name()?;
+
+            if !name.is_empty() {
+                compound.name = Some(name);
+                empty = false;
+            }
+        }
+
+        loop {
+            match self.chars.peek() {
+                Some((_, '[')) => {
+                    self.chars.next();
+                    compound.attributes.push(self.parse_attribute()?);
+                },
+                Some((_, ':')) => {
+                    self.chars.next();
+
+                    let pseudo_class = self.parse_word(|_| false);
+                    if pseudo_class != "first-child" {
+                        return Err(SelectorError::UnsupportedPseudoClass(pseudo_class.to_string()));
+                    }
+
+                    compound.first_child = true;
+                },
+                _ => break,
+            }
+
+            empty = false;
+        }
+
+        if empty {
+            return Err(self.unexpected("a tag name, `*`, `[`, or `:`"));
+        }
+
+        Ok(compound)
+    }
+
+    /// Parses an attribute selector after its opening `[`.
+    fn parse_attribute(&mut self) -> Result<AttributeSelector, SelectorError> {
+        self.skip_whitespace();
+
+        let name = self.parse_word(|c| c == ':').to_string();
+        if name.is_empty() {
+            return Err(self.unexpected("an attribute name"));
+        }
+
+        self.skip_whitespace();
+
+        let value = match self.chars.peek() {
+            Some((_, '=')) => {
+                self.chars.next();
+                self.skip_whitespace();
+                let value = self.parse_attribute_value()?;
+                self.skip_whitespace();
+                Some(value)
+            },
+            _ => None,
+        };
+
+        self.expect(']', "`]` or `=`")?;
+
+        Ok(AttributeSelector { name, value })
+    }
+
+    fn parse_attribute_value(&mut self) -> Result<String, SelectorError> {
+        let quote = match self.chars.peek() {
+            Some(&(_, c)) if c == '"' || c == '\'' => c,
+            _ => {
+                let value = self.parse_word(|c| c == '.' || c == '+');
+                if value.is_empty() {
+                    return Err(self.unexpected("an attribute value"));
+                }
+
+                return Ok(value.to_string());
+            },
+        };
+
+        self.chars.next();
+
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, c)) if c == quote => return Ok(value),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c)) => value.push(c),
+                    None => return Err(SelectorError::UnexpectedEnd { expected: "the end of the string" }),
+                },
+                Some((_, c)) => value.push(c),
+                None => return Err(SelectorError::UnexpectedEnd { expected: "the end of the string" }),
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Implementations of `syn::parse::Parse` for snax types, enabled with the
+//! `syn` feature.
+
+use proc_macro2::TokenStream;
+use syn::parse::{Parse, ParseStream};
+
+use crate::{
+    ParseError,
+    SnaxAttribute,
+    SnaxFragment,
+    SnaxItem,
+    SnaxSelfClosingTag,
+    SnaxTag,
+    parse_prefix,
+    tag_name,
+    tokenizer::parse_attribute,
+};
+
+/// Errors that point at related code, like the opening tag of a mismatched
+/// closing tag, get a second message there.
+impl From<ParseError> for syn::Error {
+    fn from(error: ParseError) -> syn::Error {
+        let mut combined = syn::Error::new(error.span(), &error);
+
+        if let ParseError::MismatchedCloseTag { open, open_generics, .. } = &error {
+            let message = format!("opening tag `<{}>` is here", tag_name(open, open_generics));
+            combined.combine(syn::Error::new(open.span(), message));
+        }
+
+        combined
+    }
+}
+
+/// Runs one of snax's parsers over the rest of a `ParseStream`, then advances
+/// the stream past exactly the tokens that were consumed.
+///
+/// `parse` returns what it parsed along with the number of top-level tokens it
+/// left untouched.
+fn parse_stream<T, F>(input: ParseStream, parse: F) -> syn::Result<T>
+    where F: FnOnce(TokenStream) -> Result<(T, usize), ParseError>
+{
+    input.step(|cursor| {
+        let tokens = cursor.token_stream();
+        let total = tokens.clone().into_iter().count();
+        let (value, remaining) = parse(tokens)?;
+
+        let mut rest = *cursor;
+        for _ in remaining..total {
+            rest = match rest.token_tree() {
+                Some((_, next)) => next,
+                None => break,
+            };
+        }
+
+        Ok((value, rest))
+    })
+}
+
+impl Parse for SnaxItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        parse_stream(input, |tokens| {
+            let (item, rest) = parse_prefix(tokens)?;
+            Ok((item, rest.into_iter().count()))
+        })
+    }
+}
+
+impl Parse for SnaxTag {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+
+        match input.parse()? {
+            SnaxItem::Tag(tag) => Ok(tag),
+            _ => Err(syn::Error::new(span, "expected a tag with children")),
+        }
+    }
+}
+
+impl Parse for SnaxSelfClosingTag {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+
+        match input.parse()? {
+            SnaxItem::SelfClosingTag(tag) => Ok(tag),
+            _ => Err(syn::Error::new(span, "expected a self-closing tag")),
+        }
+    }
+}
+
+impl Parse for SnaxFragment {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let span = input.span();
+
+        match input.parse()? {
+            SnaxItem::Fragment(fragment) => Ok(fragment),
+            _ => Err(syn::Error::new(span, "expected a fragment")),
+        }
+    }
+}
+
+impl Parse for SnaxAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        parse_stream(input, |tokens| {
+            let mut tokens = tokens.into_iter().peekable();
+            let attribute = parse_attribute(&mut tokens, "an attribute")?;
+
+            Ok((attribute, tokens.count()))
+        })
+    }
+}
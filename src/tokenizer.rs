@@ -1,9 +1,30 @@
+use std::{
+    fmt,
+    iter::Peekable,
+};
+
 use proc_macro2::{
+    TokenStream,
     TokenTree,
     Ident,
+    Punct,
+    Group,
+    Literal,
+    Delimiter,
+    Spacing,
+    Span,
 };
 
-use crate::SnaxAttribute;
+use crate::{
+    join_spans,
+    AttributeValue,
+    parse_attribute_markup,
+    ParseError,
+    SnaxAttribute,
+    SnaxName,
+    SnaxNameSeparator,
+    SnaxPath,
+};
 
 #[derive(Debug)]
 pub enum HtmlToken {
@@ -11,25 +32,56 @@ pub enum HtmlToken {
     CloseTag(HtmlCloseToken),
     SelfClosingTag(HtmlSelfClosingToken),
     Textish(HtmlTextishToken),
-    OpenFragment,
-    CloseFragment,
+    OpenFragment(Span),
+    CloseFragment(Span),
+}
+
+impl HtmlToken {
+    pub fn span(&self) -> Span {
+        match self {
+            HtmlToken::OpenTag(token) => token.span,
+            HtmlToken::CloseTag(token) => token.span,
+            HtmlToken::SelfClosingTag(token) => token.span,
+            HtmlToken::Textish(token) => token.content.span(),
+            HtmlToken::OpenFragment(span) | HtmlToken::CloseFragment(span) => *span,
+        }
+    }
+}
+
+impl fmt::Display for HtmlToken {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HtmlToken::OpenTag(token) => write!(formatter, "<{}>", token.name),
+            HtmlToken::CloseTag(token) => write!(formatter, "</{}>", token.name),
+            HtmlToken::SelfClosingTag(token) => write!(formatter, "<{} />", token.name),
+            HtmlToken::Textish(token) => write!(formatter, "{}", token.content),
+            HtmlToken::OpenFragment(_) => write!(formatter, "<>"),
+            HtmlToken::CloseFragment(_) => write!(formatter, "</>"),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct HtmlOpenToken {
-    pub name: Ident,
+    pub name: SnaxPath,
+    pub generics: Option<TokenStream>,
     pub attributes: Vec<SnaxAttribute>,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct HtmlCloseToken {
-    pub name: Ident,
+    pub name: SnaxPath,
+    pub generics: Option<TokenStream>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
 pub struct HtmlSelfClosingToken {
-    pub name: Ident,
+    pub name: SnaxPath,
+    pub generics: Option<TokenStream>,
     pub attributes: Vec<SnaxAttribute>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -37,93 +89,701 @@ pub struct HtmlTextishToken {
     pub content: TokenTree,
 }
 
+/// The part of an opening or self-closing tag that was parsed before an error,
+/// which lets the parser keep the tag around when recovering.
+#[derive(Debug)]
+pub struct HtmlPartialTag {
+    pub name: SnaxPath,
+    pub generics: Option<TokenStream>,
+    pub attributes: Vec<SnaxAttribute>,
+    pub start: Span,
+}
+
+/// How a tag's attributes ended, along with the span of its final `>`.
+pub enum HtmlTagEnd {
+    Open(Span),
+    SelfClosing(Span),
+}
+
+/// Errors from the tokenizer describe what it was expecting to find, which ends
+/// up in the message given to users.
 #[derive(Debug)]
 pub enum TokenizeError {
-    UnexpectedEnd,
-    UnexpectedToken(TokenTree),
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+    UnexpectedToken {
+        token: TokenTree,
+        expected: &'static str,
+    },
+
+    /// Markup used as an attribute value is parsed all at once, so errors
+    /// inside of it come from the parser.
+    Markup(Box<ParseError>),
+
+    /// An error after a tag's name, along with what was parsed of the tag.
+    Tag {
+        error: Box<TokenizeError>,
+        tag: Box<HtmlPartialTag>,
+    },
+}
+
+/// Grabs the next item of the iterator, returning an error describing what we
+/// expected if there isn't one.
+fn next_token<I>(input: &mut Peekable<I>, expected: &'static str) -> Result<TokenTree, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    input.next().ok_or(TokenizeError::UnexpectedEnd { expected })
 }
 
 /// Grabs the next item of the iterator, handling the None case, and then makes
 /// sure the given pattern matches.
 macro_rules! expect_next {
-    ($iterator: expr, $pattern: pat $(if $guard: expr)? => $result: expr) => {
-        match $iterator.next().ok_or(TokenizeError::UnexpectedEnd)? {
+    ($iterator: expr, $expected: expr, $pattern: pat $(if $guard: expr)? => $result: expr) => {
+        match next_token($iterator, $expected)? {
             $pattern $(if $guard)? => $result,
-            unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
+            token => return Err(TokenizeError::UnexpectedToken { token, expected: $expected }),
         }
     };
-    ($iterator: expr, $pattern: pat $(if $guard: expr)?) => {
-        expect_next!($iterator, $pattern $(if $guard)? => ())
+    ($iterator: expr, $expected: expr, $pattern: pat $(if $guard: expr)?) => {
+        expect_next!($iterator, $expected, $pattern $(if $guard)? => ())
+    };
+}
+
+/// Parses the rest of a tag or attribute name, like `data-id` or `xlink:href`,
+/// given its first ident.
+///
+/// A `:` is only treated as part of the name if it isn't the start of a `::`.
+fn parse_name<I>(first: Ident, input: &mut Peekable<I>) -> Result<SnaxName, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let mut rest = Vec::new();
+
+    loop {
+        let separator = match input.peek() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '-' => SnaxNameSeparator::Dash,
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ':' && punct.spacing() == Spacing::Alone => {
+                SnaxNameSeparator::Colon
+            },
+            _ => break,
+        };
+
+        input.next();
+
+        let expected = match separator {
+            SnaxNameSeparator::Dash => "a name after `-`",
+            SnaxNameSeparator::Colon => "a name after `:`",
+        };
+
+        let ident = expect_next!(input, expected, TokenTree::Ident(ident) => ident);
+        rest.push((separator, ident));
+    }
+
+    Ok(SnaxName {
+        first,
+        rest,
+    })
+}
+
+/// Parses a tag name, which can be a path like `crate::widgets::Button`, given
+/// its first ident.
+///
+/// Paths can end in turbofish generic arguments, like `List::<User>`, which are
+/// returned separately without their surrounding angle brackets.
+fn parse_path<I>(first: Ident, input: &mut Peekable<I>) -> Result<(SnaxPath, Option<TokenStream>), TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let first = parse_name(first, input)?;
+    let mut rest = Vec::new();
+    let mut generics = None;
+
+    loop {
+        match input.peek() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ':' && punct.spacing() == Spacing::Joint => {},
+            _ => break,
+        }
+
+        input.next();
+        expect_next!(input, "`::`", TokenTree::Punct(ref punct) if punct.as_char() == ':');
+
+        let expected = "a name or `<` after `::`";
+
+        match next_token(input, expected)? {
+            TokenTree::Ident(ident) => {
+                rest.push(parse_name(ident, input)?);
+            },
+            TokenTree::Punct(ref punct) if punct.as_char() == '<' => {
+                generics = Some(parse_generics(input)?);
+                break;
+            },
+            token => return Err(TokenizeError::UnexpectedToken { token, expected }),
+        }
+    }
+
+    let path = SnaxPath {
+        first,
+        rest,
     };
+
+    Ok((path, generics))
+}
+
+/// Parses a complete tag name, including its generic arguments. This is the
+/// part of a tag between `<` or `</` and its attributes.
+pub fn parse_tag_name<I>(input: &mut Peekable<I>) -> Result<(SnaxPath, Option<TokenStream>), TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let first = expect_next!(input, "a tag name", TokenTree::Ident(ident) => ident);
+    parse_path(first, input)
 }
 
-pub fn parse_html_token(mut input: impl Iterator<Item = TokenTree>) -> Result<HtmlToken, TokenizeError> {
-    match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
+/// Collects generic arguments up to the `>` matching an already-consumed `<`.
+///
+/// Angle brackets aren't delimiters as far as `TokenTree` is concerned, so we
+/// have to balance them ourselves, taking care not to count the `>` in `->`.
+fn parse_generics<I>(input: &mut Peekable<I>) -> Result<TokenStream, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let mut generics = Vec::new();
+    let mut depth = 1;
+    let mut after_dash = false;
+
+    loop {
+        let token = next_token(input, "`>` to close the generic arguments")?;
+        let mut is_dash = false;
+
+        if let TokenTree::Punct(ref punct) = token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if !after_dash => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return Ok(generics.into_iter().collect());
+                    }
+                },
+                '-' => is_dash = punct.spacing() == Spacing::Joint,
+                _ => {},
+            }
+        }
+
+        after_dash = is_dash;
+        generics.push(token);
+    }
+}
+
+/// Parses an attribute written as a braced group. This is either a spread like
+/// `{..value}` or `{...value}`, where both forms are accepted since the JSX
+/// spelling is what most people will reach for first, or shorthand like
+/// `{value}` for `value={value}`.
+fn parse_braced_attribute(group: Group) -> Result<SnaxAttribute, TokenizeError> {
+    let expected = "an attribute, a spread like `{..props}`, or shorthand like `{value}`";
+    let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+
+    // Shorthand is exactly one identifier, so it can't be confused with a
+    // spread, which always starts with dots. Both the name and the value
+    // point at the identifier. Raw identifiers like `r#type` are how keywords
+    // are used as variables, so the name leaves off the `r#`.
+    match tokens.as_slice() {
+        [TokenTree::Ident(ident)] if ident != "true" && ident != "false" => {
+            let name = match ident.to_string().strip_prefix("r#") {
+                Some(name) => Ident::new(name, ident.span()),
+                None => ident.clone(),
+            };
+
+            return Ok(SnaxAttribute::Simple {
+                name: SnaxName::from(name),
+                value: AttributeValue::Path {
+                    first: ident.clone(),
+                    rest: Vec::new(),
+                },
+            });
+        },
+        _ => {},
+    }
+
+    let mut tokens = tokens.into_iter().peekable();
+    let mut dots = 0;
+
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '.' {
+            break;
+        }
+
+        dots += 1;
+        tokens.next();
+    }
+
+    let value: TokenStream = tokens.collect();
+
+    // Every leading dot is counted, so `{....x}` is rejected instead of
+    // becoming a spread of `.x`.
+    if !(2..=3).contains(&dots) || value.is_empty() {
+        return Err(TokenizeError::UnexpectedToken {
+            token: TokenTree::Group(group),
+            expected,
+        });
+    }
+
+    Ok(SnaxAttribute::Spread {
+        value,
+        span: group.span(),
+    })
+}
+
+/// Parses a single attribute, which is either a name with an optional value, a
+/// spread, or shorthand. `expected` describes what the caller would accept at
+/// this point if there's no attribute.
+pub fn parse_attribute<I>(input: &mut Peekable<I>, expected: &'static str) -> Result<SnaxAttribute, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    match next_token(input, expected)? {
+        TokenTree::Ident(name) => {
+            let name = parse_name(name, input)?;
+
+            // Attributes without a value, like HTML's `<input disabled />`, are
+            // followed directly by another attribute, `>`, or `/`.
+            match input.peek() {
+                Some(TokenTree::Punct(ref punct)) if punct.as_char() == '=' => {
+                    input.next();
+                },
+                _ => return Ok(SnaxAttribute::Flag { name }),
+            }
+
+            if let Some(TokenTree::Punct(ref punct)) = input.peek() {
+                if punct.as_char() == '<' {
+                    let value = parse_attribute_markup(input)
+                        .map_err(|error| TokenizeError::Markup(Box::new(error)))?;
+
+                    return Ok(SnaxAttribute::Markup {
+                        name,
+                        value: Box::new(value),
+                    });
+                }
+            }
+
+            Ok(SnaxAttribute::Simple {
+                name,
+                value: parse_attribute_value(input)?,
+            })
+        },
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+            parse_braced_attribute(group)
+        },
+        token => Err(TokenizeError::UnexpectedToken { token, expected }),
+    }
+}
+
+/// Parses the value of an attribute after its `=`, when that value isn't
+/// markup.
+fn parse_attribute_value<I>(input: &mut Peekable<I>) -> Result<AttributeValue, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let expected = "a literal, a block, a path, or markup as the attribute value";
+
+    match next_token(input, expected)? {
+        TokenTree::Literal(literal) => Ok(AttributeValue::Literal(literal)),
+        TokenTree::Group(group) => Ok(AttributeValue::Block(group)),
+        TokenTree::Punct(minus) if minus.as_char() == '-' => {
+            let expected = "a number after `-`";
+
+            match next_token(input, expected)? {
+                TokenTree::Literal(literal) if is_numeric(&literal) => {
+                    Ok(AttributeValue::Negative { minus, literal })
+                },
+                token => Err(TokenizeError::UnexpectedToken { token, expected }),
+            }
+        },
+        TokenTree::Ident(ident) if ident == "true" || ident == "false" => {
+            Ok(AttributeValue::Bool {
+                value: ident == "true",
+                span: ident.span(),
+            })
+        },
+        TokenTree::Ident(first) => {
+            let mut rest = Vec::new();
+
+            loop {
+                match input.peek() {
+                    Some(TokenTree::Punct(ref punct)) if punct.as_char() == ':' && punct.spacing() == Spacing::Joint => {},
+                    _ => break,
+                }
+
+                input.next();
+                expect_next!(input, "`::`", TokenTree::Punct(ref punct) if punct.as_char() == ':');
+
+                rest.push(expect_next!(input, "a name after `::`", TokenTree::Ident(ident) => ident));
+            }
+
+            Ok(AttributeValue::Path { first, rest })
+        },
+        token => Err(TokenizeError::UnexpectedToken { token, expected }),
+    }
+}
+
+/// Tells whether a literal is a number, which are the only literals that can
+/// be negated.
+fn is_numeric(literal: &Literal) -> bool {
+    literal.to_string()
+        .starts_with(|c: char| c.is_ascii_digit())
+}
+
+pub fn parse_html_token<I>(input: &mut Peekable<I>) -> Result<HtmlToken, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let expected = "a tag, a fragment, a literal, or a block";
+
+    match next_token(input, expected)? {
         TokenTree::Punct(ref punct) if punct.as_char() == '<' => {
-            match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
+            let start = punct.span();
+            let expected = "a tag name, `>`, or `/`";
+
+            match next_token(input, expected)? {
                 TokenTree::Punct(ref punct) if punct.as_char() == '/' => {
-                    match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
+                    let expected = "a tag name or `>` after `</`";
+
+                    match next_token(input, expected)? {
                         TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
-                            Ok(HtmlToken::CloseFragment)
+                            Ok(HtmlToken::CloseFragment(join_spans(start, punct.span())))
                         },
                         TokenTree::Ident(name) => {
-                            expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '>');
+                            let (name, generics) = parse_path(name, input)?;
+
+                            let end = expect_next!(input, "`>` to finish the closing tag",
+                                TokenTree::Punct(ref punct) if punct.as_char() == '>' => punct.span());
 
                             Ok(HtmlToken::CloseTag(HtmlCloseToken {
                                 name,
+                                generics,
+                                span: join_spans(start, end),
                             }))
                         },
-                        unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
+                        token => Err(TokenizeError::UnexpectedToken { token, expected }),
                     }
                 },
                 TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
-                    Ok(HtmlToken::OpenFragment)
+                    Ok(HtmlToken::OpenFragment(join_spans(start, punct.span())))
                 },
                 TokenTree::Ident(name) => {
+                    let (name, generics) = parse_path(name, input)?;
                     let mut attributes = Vec::new();
 
-                    loop {
-                        match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
-                            TokenTree::Ident(attribute_name) => {
-                                expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '=');
-
-                                match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
-                                    value @ TokenTree::Literal(_) | value @ TokenTree::Group(_) => {
-                                        attributes.push(SnaxAttribute::Simple {
-                                            name: attribute_name,
-                                            value,
-                                        });
-                                    },
-                                    unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
-                                }
-                            },
-                            TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
-                                // Opening tag
-
-                                return Ok(HtmlToken::OpenTag(HtmlOpenToken {
-                                    name,
-                                    attributes,
-                                }));
-                            },
-                            TokenTree::Punct(ref punct) if punct.as_char() == '/' => {
-                                // Self-closing tag
-
-                                expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '>');
-
-                                return Ok(HtmlToken::SelfClosingTag(HtmlSelfClosingToken {
-                                    name,
-                                    attributes,
-                                }));
-                            },
-                            unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
-                        }
+                    match parse_tag_end(input, &mut attributes) {
+                        Ok(HtmlTagEnd::Open(end)) => Ok(HtmlToken::OpenTag(HtmlOpenToken {
+                            name,
+                            generics,
+                            attributes,
+                            span: join_spans(start, end),
+                        })),
+                        Ok(HtmlTagEnd::SelfClosing(end)) => Ok(HtmlToken::SelfClosingTag(HtmlSelfClosingToken {
+                            name,
+                            generics,
+                            attributes,
+                            span: join_spans(start, end),
+                        })),
+                        Err(error) => Err(TokenizeError::Tag {
+                            error: Box::new(error),
+                            tag: Box::new(HtmlPartialTag {
+                                name,
+                                generics,
+                                attributes,
+                                start,
+                            }),
+                        }),
                     }
                 },
-                unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
+                token => Err(TokenizeError::UnexpectedToken { token, expected }),
             }
         },
         content @ TokenTree::Literal(_) => Ok(HtmlToken::Textish(HtmlTextishToken { content })),
         content @ TokenTree::Group(_) => Ok(HtmlToken::Textish(HtmlTextishToken { content })),
-        unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
+        token => Err(TokenizeError::UnexpectedToken { token, expected }),
+    }
+}
+
+/// Parses the attributes of a tag up to and including its `>` or `/>`, adding
+/// them to `attributes` as it goes.
+fn parse_tag_end<I>(input: &mut Peekable<I>, attributes: &mut Vec<SnaxAttribute>) -> Result<HtmlTagEnd, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let expected = "an attribute, `>`, or `/>`";
+
+    loop {
+        match input.peek() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '>' || punct.as_char() == '/' => {},
+            _ => {
+                attributes.push(parse_attribute(input, expected)?);
+                continue;
+            },
+        }
+
+        match next_token(input, expected)? {
+            TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
+                return Ok(HtmlTagEnd::Open(punct.span()));
+            },
+            TokenTree::Punct(ref punct) if punct.as_char() == '/' => {
+                let end = expect_next!(input, "`>` after `/`",
+                    TokenTree::Punct(ref punct) if punct.as_char() == '>' => punct.span());
+
+                return Ok(HtmlTagEnd::SelfClosing(end));
+            },
+            token => return Err(TokenizeError::UnexpectedToken { token, expected }),
+        }
+    }
+}
+
+/// Tells whether a token is the given punctuation character.
+pub fn is_punct(token: &TokenTree, c: char) -> bool {
+    match token {
+        TokenTree::Punct(punct) => punct.as_char() == c,
+        _ => false,
+    }
+}
+
+/// Tells whether a token is the given keyword.
+fn is_keyword(token: &TokenTree, keyword: &str) -> bool {
+    match token {
+        TokenTree::Ident(ident) => ident == keyword,
+        _ => false,
+    }
+}
+
+/// Tells whether the next token starts a control-flow item like `@if`.
+pub fn peek_control_flow<I>(input: &mut Peekable<I>) -> bool
+    where I: Iterator<Item = TokenTree>
+{
+    match input.peek() {
+        Some(token) => is_punct(token, '@'),
+        None => false,
+    }
+}
+
+/// Parses the `@` and keyword at the start of a control-flow item.
+pub fn parse_control_keyword<I>(input: &mut Peekable<I>) -> Result<(Punct, Ident), TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let at = expect_next!(input, "`@`", TokenTree::Punct(punct) if punct.as_char() == '@' => punct);
+
+    let expected = "`if`, `for`, or `match` after `@`";
+    let keyword = expect_next!(input, expected,
+        TokenTree::Ident(ident) if ident == "if" || ident == "for" || ident == "match" => ident);
+
+    Ok((at, keyword))
+}
+
+/// Collects tokens up to the next braced block, returning them along with the
+/// block. This is how the condition of an `@if` or the iterator of a `@for` is
+/// found, since Rust doesn't allow struct literals in those positions either.
+/// Patterns can have braces of their own, like `Point { x, y }`, so they need
+/// to be collected first.
+///
+/// `head` describes the tokens before the block and `block` describes the
+/// block itself, and are used when either of them is missing.
+pub fn parse_block_head<I>(
+    input: &mut Peekable<I>,
+    head: &'static str,
+    block: &'static str,
+) -> Result<(Vec<TokenTree>, Group), TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let mut tokens = Vec::new();
+
+    loop {
+        let expected = if tokens.is_empty() { head } else { block };
+
+        match next_token(input, expected)? {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                if tokens.is_empty() {
+                    return Err(TokenizeError::UnexpectedToken {
+                        token: TokenTree::Group(group),
+                        expected: head,
+                    });
+                }
+
+                return Ok((tokens, group));
+            },
+            token => tokens.push(token),
+        }
+    }
+}
+
+/// Parses the pattern of a `@for`, along with the `in` keyword after it.
+pub fn parse_for_pattern<I>(input: &mut Peekable<I>) -> Result<(Vec<TokenTree>, Ident), TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let mut pattern = Vec::new();
+
+    loop {
+        let expected = if pattern.is_empty() { "a pattern after `@for`" } else { "`in` after the pattern" };
+
+        match next_token(input, expected)? {
+            TokenTree::Ident(ident) if ident == "in" && !pattern.is_empty() => return Ok((pattern, ident)),
+            token => pattern.push(token),
+        }
+    }
+}
+
+/// Parses the condition of an `@if` or `else if`, along with the block for its
+/// body.
+///
+/// The pattern of an `if let` is collected up to its `=` before looking for
+/// the block, since struct patterns like `Point { x, y }` contain braces.
+pub fn parse_if_condition<I>(input: &mut Peekable<I>) -> Result<(Vec<TokenTree>, Group), TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let mut condition = Vec::new();
+
+    match input.peek() {
+        Some(token) if is_keyword(token, "let") => {},
+        _ => return parse_block_head(input, "a condition after `if`", "a block for the body of `@if`"),
     }
-}
\ No newline at end of file
+
+    loop {
+        let expected = if condition.len() < 2 { "a pattern after `let`" } else { "`=` after the pattern" };
+        let token = next_token(input, expected)?;
+
+        // The `=` in operators like `..=` and `==` is joined to a neighbor.
+        let is_assignment = match (&token, condition.last()) {
+            (TokenTree::Punct(punct), previous) if punct.as_char() == '=' && punct.spacing() == Spacing::Alone => {
+                match previous {
+                    Some(TokenTree::Punct(previous)) => previous.spacing() == Spacing::Alone,
+                    _ => true,
+                }
+            },
+            _ => false,
+        };
+
+        condition.push(token);
+
+        if is_assignment && condition.len() > 2 {
+            break;
+        }
+    }
+
+    let (expression, block) = parse_block_head(input, "an expression after `=`", "a block for the body of `@if`")?;
+    condition.extend(expression);
+
+    Ok((condition, block))
+}
+
+/// Consumes an `else` keyword if it's next.
+pub fn parse_else<I>(input: &mut Peekable<I>) -> Option<Ident>
+    where I: Iterator<Item = TokenTree>
+{
+    match input.peek() {
+        Some(token) if is_keyword(token, "else") => {},
+        _ => return None,
+    }
+
+    match input.next() {
+        Some(TokenTree::Ident(ident)) => Some(ident),
+        _ => unreachable!("token was checked to be `else`"),
+    }
+}
+
+/// Consumes an `if` keyword if it's next, which continues an `else`.
+pub fn parse_else_if<I>(input: &mut Peekable<I>) -> Option<Ident>
+    where I: Iterator<Item = TokenTree>
+{
+    match input.peek() {
+        Some(token) if is_keyword(token, "if") => {},
+        _ => return None,
+    }
+
+    match input.next() {
+        Some(TokenTree::Ident(ident)) => Some(ident),
+        _ => unreachable!("token was checked to be `if`"),
+    }
+}
+
+/// The start of an arm of a `@match`, up to and including its `=>`.
+#[derive(Debug)]
+pub struct MatchArmHead {
+    pub pattern: Vec<TokenTree>,
+    pub guard: Option<(Ident, Vec<TokenTree>)>,
+    pub arrow: (Punct, Punct),
+}
+
+/// Parses the pattern and optional guard of a `@match` arm, along with the
+/// `=>` after them.
+pub fn parse_match_arm_head<I>(input: &mut Peekable<I>) -> Result<MatchArmHead, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    let mut pattern = Vec::new();
+    let mut guard: Option<(Ident, Vec<TokenTree>)> = None;
+
+    loop {
+        let expected = if pattern.is_empty() { "a pattern" } else { "`=>` after the pattern" };
+
+        let token = next_token(input, expected)?;
+
+        if let TokenTree::Punct(punct) = &token {
+            let is_arrow_start = punct.as_char() == '=' && punct.spacing() == Spacing::Joint;
+            let arrow_follows = match input.peek() {
+                Some(next) => is_punct(next, '>'),
+                None => false,
+            };
+
+            if is_arrow_start && arrow_follows && !pattern.is_empty() {
+                let gt = match input.next() {
+                    Some(TokenTree::Punct(gt)) => gt,
+                    _ => unreachable!("token was checked to be `>`"),
+                };
+
+                return Ok(MatchArmHead {
+                    pattern,
+                    guard,
+                    arrow: (punct.clone(), gt),
+                });
+            }
+        }
+
+        match &mut guard {
+            Some((_, condition)) => condition.push(token),
+            None => match token {
+                TokenTree::Ident(ident) if ident == "if" && !pattern.is_empty() => {
+                    guard = Some((ident, Vec::new()));
+                },
+                token => pattern.push(token),
+            },
+        }
+    }
+}
+
+/// Consumes a `,` if it's next, which separates `@match` arms.
+pub fn parse_comma<I>(input: &mut Peekable<I>) -> Option<Punct>
+    where I: Iterator<Item = TokenTree>
+{
+    match input.peek() {
+        Some(token) if is_punct(token, ',') => {},
+        _ => return None,
+    }
+
+    match input.next() {
+        Some(TokenTree::Punct(punct)) => Some(punct),
+        _ => unreachable!("token was checked to be `,`"),
+    }
+}
+
+/// Takes a braced block if it's next.
+pub fn parse_optional_block<I>(input: &mut Peekable<I>) -> Option<Group>
+    where I: Iterator<Item = TokenTree>
+{
+    match input.peek() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {},
+        _ => return None,
+    }
+
+    match input.next() {
+        Some(TokenTree::Group(group)) => Some(group),
+        _ => unreachable!("token was checked to be a block"),
+    }
+}
+
+/// Takes a braced block, which must be next.
+pub fn parse_block<I>(input: &mut Peekable<I>, expected: &'static str) -> Result<Group, TokenizeError>
+    where I: Iterator<Item = TokenTree>
+{
+    Ok(expect_next!(input, expected,
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => group))
+}
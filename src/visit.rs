@@ -0,0 +1,228 @@
+//! Traversal over a borrowed syntax tree, in the style of `syn::visit`.
+//!
+//! Each method of [`Visit`] has a default implementation that calls the free
+//! function of the same name, which visits the node's children. Override only
+//! the methods for the nodes you're interested in, and call the free function
+//! from your override to keep walking into children.
+//!
+//! ```
+//! use snax::{SnaxPath, visit::Visit};
+//!
+//! struct TagNames(Vec<String>);
+//!
+//! impl<'ast> Visit<'ast> for TagNames {
+//!     fn visit_path(&mut self, path: &'ast SnaxPath) {
+//!         self.0.push(path.to_string());
+//!     }
+//! }
+//! ```
+//!
+//! [`Visit`]: trait.Visit.html
+
+use proc_macro2::TokenTree;
+
+use crate::{
+    SnaxAttribute,
+    SnaxExpression,
+    SnaxExpressionPart,
+    SnaxFor,
+    SnaxFragment,
+    SnaxIf,
+    SnaxItem,
+    SnaxMatch,
+    SnaxMatchArm,
+    SnaxName,
+    SnaxPath,
+    SnaxSelfClosingTag,
+    SnaxTag,
+};
+
+/// Visits each node of a syntax tree by shared reference.
+pub trait Visit<'ast> {
+    fn visit_item(&mut self, node: &'ast SnaxItem) {
+        visit_item(self, node);
+    }
+
+    fn visit_tag(&mut self, node: &'ast SnaxTag) {
+        visit_tag(self, node);
+    }
+
+    fn visit_self_closing_tag(&mut self, node: &'ast SnaxSelfClosingTag) {
+        visit_self_closing_tag(self, node);
+    }
+
+    fn visit_fragment(&mut self, node: &'ast SnaxFragment) {
+        visit_fragment(self, node);
+    }
+
+    fn visit_content(&mut self, node: &'ast TokenTree) {
+        visit_content(self, node);
+    }
+
+    /// Visits a block of content or an attribute value with markup embedded
+    /// in it.
+    fn visit_expression(&mut self, node: &'ast SnaxExpression) {
+        visit_expression(self, node);
+    }
+
+    fn visit_if(&mut self, node: &'ast SnaxIf) {
+        visit_if(self, node);
+    }
+
+    fn visit_for(&mut self, node: &'ast SnaxFor) {
+        visit_for(self, node);
+    }
+
+    fn visit_match(&mut self, node: &'ast SnaxMatch) {
+        visit_match(self, node);
+    }
+
+    fn visit_match_arm(&mut self, node: &'ast SnaxMatchArm) {
+        visit_match_arm(self, node);
+    }
+
+    fn visit_attribute(&mut self, node: &'ast SnaxAttribute) {
+        visit_attribute(self, node);
+    }
+
+    /// Visits the name of a tag.
+    fn visit_path(&mut self, node: &'ast SnaxPath) {
+        visit_path(self, node);
+    }
+
+    /// Visits the name of an attribute or a segment of a tag's name.
+    fn visit_name(&mut self, node: &'ast SnaxName) {
+        visit_name(self, node);
+    }
+}
+
+pub fn visit_item<'ast, V>(visitor: &mut V, node: &'ast SnaxItem)
+    where V: Visit<'ast> + ?Sized
+{
+    match node {
+        SnaxItem::Tag(tag) => visitor.visit_tag(tag),
+        SnaxItem::SelfClosingTag(tag) => visitor.visit_self_closing_tag(tag),
+        SnaxItem::Fragment(fragment) => visitor.visit_fragment(fragment),
+        SnaxItem::Content(content) => visitor.visit_content(content),
+        SnaxItem::Expression(expression) => visitor.visit_expression(expression),
+        SnaxItem::If(node) => visitor.visit_if(node),
+        SnaxItem::For(node) => visitor.visit_for(node),
+        SnaxItem::Match(node) => visitor.visit_match(node),
+    }
+}
+
+pub fn visit_tag<'ast, V>(visitor: &mut V, node: &'ast SnaxTag)
+    where V: Visit<'ast> + ?Sized
+{
+    visitor.visit_path(&node.name);
+
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+
+    for child in &node.children {
+        visitor.visit_item(child);
+    }
+}
+
+pub fn visit_self_closing_tag<'ast, V>(visitor: &mut V, node: &'ast SnaxSelfClosingTag)
+    where V: Visit<'ast> + ?Sized
+{
+    visitor.visit_path(&node.name);
+
+    for attribute in &node.attributes {
+        visitor.visit_attribute(attribute);
+    }
+}
+
+pub fn visit_fragment<'ast, V>(visitor: &mut V, node: &'ast SnaxFragment)
+    where V: Visit<'ast> + ?Sized
+{
+    for child in &node.children {
+        visitor.visit_item(child);
+    }
+}
+
+pub fn visit_content<'ast, V>(_visitor: &mut V, _node: &'ast TokenTree)
+    where V: Visit<'ast> + ?Sized
+{
+}
+
+pub fn visit_expression<'ast, V>(visitor: &mut V, node: &'ast SnaxExpression)
+    where V: Visit<'ast> + ?Sized
+{
+    for part in &node.parts {
+        match part {
+            SnaxExpressionPart::Token(_) => {},
+            SnaxExpressionPart::Group(group) => visitor.visit_expression(group),
+            SnaxExpressionPart::Item(item) => visitor.visit_item(item),
+        }
+    }
+}
+
+pub fn visit_if<'ast, V>(visitor: &mut V, node: &'ast SnaxIf)
+    where V: Visit<'ast> + ?Sized
+{
+    for child in &node.then_branch {
+        visitor.visit_item(child);
+    }
+
+    for child in node.else_branch.iter().flatten() {
+        visitor.visit_item(child);
+    }
+}
+
+pub fn visit_for<'ast, V>(visitor: &mut V, node: &'ast SnaxFor)
+    where V: Visit<'ast> + ?Sized
+{
+    for child in &node.body {
+        visitor.visit_item(child);
+    }
+}
+
+pub fn visit_match<'ast, V>(visitor: &mut V, node: &'ast SnaxMatch)
+    where V: Visit<'ast> + ?Sized
+{
+    for arm in &node.arms {
+        visitor.visit_match_arm(arm);
+    }
+}
+
+pub fn visit_match_arm<'ast, V>(visitor: &mut V, node: &'ast SnaxMatchArm)
+    where V: Visit<'ast> + ?Sized
+{
+    for child in &node.body {
+        visitor.visit_item(child);
+    }
+}
+
+pub fn visit_attribute<'ast, V>(visitor: &mut V, node: &'ast SnaxAttribute)
+    where V: Visit<'ast> + ?Sized
+{
+    match node {
+        SnaxAttribute::Simple { name, .. } => visitor.visit_name(name),
+        SnaxAttribute::Flag { name } => visitor.visit_name(name),
+        SnaxAttribute::Spread { .. } => {},
+        SnaxAttribute::Markup { name, value } => {
+            visitor.visit_name(name);
+            visitor.visit_item(value);
+        },
+        SnaxAttribute::Expression { name, value } => {
+            visitor.visit_name(name);
+            visitor.visit_expression(value);
+        },
+    }
+}
+
+pub fn visit_path<'ast, V>(visitor: &mut V, node: &'ast SnaxPath)
+    where V: Visit<'ast> + ?Sized
+{
+    for segment in node.segments() {
+        visitor.visit_name(segment);
+    }
+}
+
+pub fn visit_name<'ast, V>(_visitor: &mut V, _node: &'ast SnaxName)
+    where V: Visit<'ast> + ?Sized
+{
+}
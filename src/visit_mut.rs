@@ -0,0 +1,232 @@
+//! Traversal over a mutably borrowed syntax tree, in the style of
+//! `syn::visit_mut`.
+//!
+//! This works the same way as [`visit`](../visit/index.html), but each node is
+//! given by mutable reference so that it can be changed in place.
+//!
+//! ```
+//! use proc_macro2::{Ident, Span};
+//! use snax::{SnaxAttribute, SnaxSelfClosingTag, visit_mut::{self, VisitMut}};
+//!
+//! struct AddKeys;
+//!
+//! impl VisitMut for AddKeys {
+//!     fn visit_self_closing_tag_mut(&mut self, tag: &mut SnaxSelfClosingTag) {
+//!         tag.attributes.push(SnaxAttribute::Flag {
+//!             name: Ident::new("key", Span::call_site()).into(),
+//!         });
+//!
+//!         visit_mut::visit_self_closing_tag_mut(self, tag);
+//!     }
+//! }
+//! ```
+
+use proc_macro2::TokenTree;
+
+use crate::{
+    SnaxAttribute,
+    SnaxExpression,
+    SnaxExpressionPart,
+    SnaxFor,
+    SnaxFragment,
+    SnaxIf,
+    SnaxItem,
+    SnaxMatch,
+    SnaxMatchArm,
+    SnaxName,
+    SnaxPath,
+    SnaxSelfClosingTag,
+    SnaxTag,
+};
+
+/// Visits each node of a syntax tree by mutable reference.
+pub trait VisitMut {
+    fn visit_item_mut(&mut self, node: &mut SnaxItem) {
+        visit_item_mut(self, node);
+    }
+
+    fn visit_tag_mut(&mut self, node: &mut SnaxTag) {
+        visit_tag_mut(self, node);
+    }
+
+    fn visit_self_closing_tag_mut(&mut self, node: &mut SnaxSelfClosingTag) {
+        visit_self_closing_tag_mut(self, node);
+    }
+
+    fn visit_fragment_mut(&mut self, node: &mut SnaxFragment) {
+        visit_fragment_mut(self, node);
+    }
+
+    fn visit_content_mut(&mut self, node: &mut TokenTree) {
+        visit_content_mut(self, node);
+    }
+
+    /// Visits a block of content or an attribute value with markup embedded
+    /// in it.
+    fn visit_expression_mut(&mut self, node: &mut SnaxExpression) {
+        visit_expression_mut(self, node);
+    }
+
+    fn visit_if_mut(&mut self, node: &mut SnaxIf) {
+        visit_if_mut(self, node);
+    }
+
+    fn visit_for_mut(&mut self, node: &mut SnaxFor) {
+        visit_for_mut(self, node);
+    }
+
+    fn visit_match_mut(&mut self, node: &mut SnaxMatch) {
+        visit_match_mut(self, node);
+    }
+
+    fn visit_match_arm_mut(&mut self, node: &mut SnaxMatchArm) {
+        visit_match_arm_mut(self, node);
+    }
+
+    fn visit_attribute_mut(&mut self, node: &mut SnaxAttribute) {
+        visit_attribute_mut(self, node);
+    }
+
+    /// Visits the name of a tag.
+    fn visit_path_mut(&mut self, node: &mut SnaxPath) {
+        visit_path_mut(self, node);
+    }
+
+    /// Visits the name of an attribute or a segment of a tag's name.
+    fn visit_name_mut(&mut self, node: &mut SnaxName) {
+        visit_name_mut(self, node);
+    }
+}
+
+pub fn visit_item_mut<V>(visitor: &mut V, node: &mut SnaxItem)
+    where V: VisitMut + ?Sized
+{
+    match node {
+        SnaxItem::Tag(tag) => visitor.visit_tag_mut(tag),
+        SnaxItem::SelfClosingTag(tag) => visitor.visit_self_closing_tag_mut(tag),
+        SnaxItem::Fragment(fragment) => visitor.visit_fragment_mut(fragment),
+        SnaxItem::Content(content) => visitor.visit_content_mut(content),
+        SnaxItem::Expression(expression) => visitor.visit_expression_mut(expression),
+        SnaxItem::If(node) => visitor.visit_if_mut(node),
+        SnaxItem::For(node) => visitor.visit_for_mut(node),
+        SnaxItem::Match(node) => visitor.visit_match_mut(node),
+    }
+}
+
+pub fn visit_tag_mut<V>(visitor: &mut V, node: &mut SnaxTag)
+    where V: VisitMut + ?Sized
+{
+    visitor.visit_path_mut(&mut node.name);
+
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+
+    for child in &mut node.children {
+        visitor.visit_item_mut(child);
+    }
+}
+
+pub fn visit_self_closing_tag_mut<V>(visitor: &mut V, node: &mut SnaxSelfClosingTag)
+    where V: VisitMut + ?Sized
+{
+    visitor.visit_path_mut(&mut node.name);
+
+    for attribute in &mut node.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+}
+
+pub fn visit_fragment_mut<V>(visitor: &mut V, node: &mut SnaxFragment)
+    where V: VisitMut + ?Sized
+{
+    for child in &mut node.children {
+        visitor.visit_item_mut(child);
+    }
+}
+
+pub fn visit_content_mut<V>(_visitor: &mut V, _node: &mut TokenTree)
+    where V: VisitMut + ?Sized
+{
+}
+
+pub fn visit_expression_mut<V>(visitor: &mut V, node: &mut SnaxExpression)
+    where V: VisitMut + ?Sized
+{
+    for part in &mut node.parts {
+        match part {
+            SnaxExpressionPart::Token(_) => {},
+            SnaxExpressionPart::Group(group) => visitor.visit_expression_mut(group),
+            SnaxExpressionPart::Item(item) => visitor.visit_item_mut(item),
+        }
+    }
+}
+
+pub fn visit_if_mut<V>(visitor: &mut V, node: &mut SnaxIf)
+    where V: VisitMut + ?Sized
+{
+    for child in &mut node.then_branch {
+        visitor.visit_item_mut(child);
+    }
+
+    for child in node.else_branch.iter_mut().flatten() {
+        visitor.visit_item_mut(child);
+    }
+}
+
+pub fn visit_for_mut<V>(visitor: &mut V, node: &mut SnaxFor)
+    where V: VisitMut + ?Sized
+{
+    for child in &mut node.body {
+        visitor.visit_item_mut(child);
+    }
+}
+
+pub fn visit_match_mut<V>(visitor: &mut V, node: &mut SnaxMatch)
+    where V: VisitMut + ?Sized
+{
+    for arm in &mut node.arms {
+        visitor.visit_match_arm_mut(arm);
+    }
+}
+
+pub fn visit_match_arm_mut<V>(visitor: &mut V, node: &mut SnaxMatchArm)
+    where V: VisitMut + ?Sized
+{
+    for child in &mut node.body {
+        visitor.visit_item_mut(child);
+    }
+}
+
+pub fn visit_attribute_mut<V>(visitor: &mut V, node: &mut SnaxAttribute)
+    where V: VisitMut + ?Sized
+{
+    match node {
+        SnaxAttribute::Simple { name, .. } => visitor.visit_name_mut(name),
+        SnaxAttribute::Flag { name } => visitor.visit_name_mut(name),
+        SnaxAttribute::Spread { .. } => {},
+        SnaxAttribute::Markup { name, value } => {
+            visitor.visit_name_mut(name);
+            visitor.visit_item_mut(value);
+        },
+        SnaxAttribute::Expression { name, value } => {
+            visitor.visit_name_mut(name);
+            visitor.visit_expression_mut(value);
+        },
+    }
+}
+
+pub fn visit_path_mut<V>(visitor: &mut V, node: &mut SnaxPath)
+    where V: VisitMut + ?Sized
+{
+    visitor.visit_name_mut(&mut node.first);
+
+    for segment in &mut node.rest {
+        visitor.visit_name_mut(segment);
+    }
+}
+
+pub fn visit_name_mut<V>(_visitor: &mut V, _node: &mut SnaxName)
+    where V: VisitMut + ?Sized
+{
+}
@@ -0,0 +1,128 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+
+use snax::cst::{self, CstAttribute, CstItem};
+
+/// Flattens a token stream into strings, keeping punctuation spacing so that
+/// `</` and `< /` are told apart.
+fn describe(stream: TokenStream) -> Vec<String> {
+    stream.into_iter()
+        .map(|token| match token {
+            TokenTree::Punct(punct) => format!("{}{:?}", punct.as_char(), punct.spacing()),
+            other => other.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn prints_original_tokens() {
+    let input: TokenStream = r#"
+        <ui::List::<Vec<User>> class="a" data-id={ 5 } hidden {..props}>
+            <br/>
+            "text"
+            <></>
+        </ui::List>
+    "#.parse().unwrap();
+
+    let tree = cst::parse(input.clone()).unwrap();
+
+    assert_eq!(describe(tree.to_token_stream()), describe(input));
+}
+
+#[test]
+fn keeps_punctuation() {
+    let tree = cst::parse(quote!(<input value="a" disabled />)).unwrap();
+
+    let tag = match &tree {
+        CstItem::SelfClosingTag(tag) => tag,
+        _ => panic!("expected a self-closing tag, got {:?}", tree),
+    };
+
+    assert_eq!(tag.lt.as_char(), '<');
+    assert_eq!(tag.slash.as_char(), '/');
+    assert_eq!(tag.gt.as_char(), '>');
+    assert_eq!(tag.attributes.len(), 2);
+
+    match &tag.attributes[0] {
+        CstAttribute::Simple { name, eq, value } => {
+            assert_eq!(name.len(), 1);
+            assert_eq!(eq.as_char(), '=');
+            assert_eq!(value.len(), 1);
+        },
+        other => panic!("expected a simple attribute, got {:?}", other),
+    }
+}
+
+#[test]
+fn lowers_to_ast() {
+    let input = quote!(
+        <div class="a">
+            <span data-id="1">"Hello"</span>
+            <>{ world }</>
+        </div>
+    );
+
+    let tree = cst::parse(input.clone()).unwrap();
+
+    assert_eq!(tree.lower().unwrap(), snax::parse(input).unwrap());
+}
+
+#[test]
+fn rejects_what_parse_rejects() {
+    assert!(cst::parse(quote!(<div></span>)).is_err());
+    assert!(cst::parse(quote!(<div>)).is_err());
+    assert!(cst::parse(quote!(<div /> 5)).is_err());
+}
+
+#[test]
+fn control_flow_round_trip() {
+    let input: TokenStream = r#"
+        <ul>
+            @for user in users { <li>{ user }</li> }
+            @if a { "a" } else if b { <b/> } else { }
+            @if let Point { x, .. } = p { { x } } else if let 1..=5 = n { }
+            @match x { Some(y) if y > 1 => <p/>, _ => { "none" } }
+        </ul>
+    "#.parse().unwrap();
+
+    let tree = cst::parse(input.clone()).unwrap();
+
+    assert_eq!(describe(tree.to_token_stream()), describe(input.clone()));
+    assert_eq!(tree.lower().unwrap(), snax::parse(input).unwrap());
+}
+
+#[test]
+fn markup_attribute_round_trip() {
+    let input: TokenStream = r#"
+        <Layout header=<Header title="Home"/> footer=<>"x"</>>
+            <p/>
+        </Layout>
+    "#.parse().unwrap();
+
+    let tree = cst::parse(input.clone()).unwrap();
+
+    assert_eq!(describe(tree.to_token_stream()), describe(input.clone()));
+    assert_eq!(tree.lower().unwrap(), snax::parse(input).unwrap());
+
+    match &tree {
+        CstItem::Tag(tag) => match &tag.open.attributes[0] {
+            CstAttribute::Markup { value, .. } => assert!(matches!(**value, CstItem::SelfClosingTag(_))),
+            other => panic!("expected a markup attribute, got {:?}", other),
+        },
+        other => panic!("expected a tag, got {:?}", other),
+    }
+}
+
+#[test]
+fn shorthand_round_trip() {
+    let input: TokenStream = "<Input {value} min=-1 kind=Kind::Wide {..rest} />".parse().unwrap();
+    let tree = cst::parse(input.clone()).unwrap();
+
+    assert_eq!(describe(tree.to_token_stream()), describe(input.clone()));
+    assert_eq!(tree.lower().unwrap(), snax::parse(input).unwrap());
+
+    match &tree {
+        CstItem::SelfClosingTag(tag) => assert!(matches!(tag.attributes[0], CstAttribute::Shorthand(_))),
+        other => panic!("expected a self-closing tag, got {:?}", other),
+    }
+}
@@ -0,0 +1,160 @@
+use proc_macro2::{Delimiter, TokenTree};
+use quote::quote;
+
+use snax::{SnaxAttribute, SnaxExpression, SnaxExpressionPart, SnaxItem};
+
+fn expression(item: &SnaxItem) -> &SnaxExpression {
+    match item {
+        SnaxItem::Expression(expression) => expression,
+        other => panic!("expected an expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn markup_in_closure() {
+    let item = snax::parse_embedded(quote!(
+        <ul>{ users.iter().map(|user| <li>{ user }</li>) }</ul>
+    )).unwrap();
+
+    let expression = expression(&item.children()[0]);
+    let items = expression.items();
+
+    assert_eq!(expression.delimiter, Delimiter::Brace);
+    assert_eq!(items, vec![&snax::parse(quote!(<li>{ user }</li>)).unwrap()]);
+
+    // The closure's parentheses are the only group with markup in them.
+    let groups: Vec<_> = expression.parts.iter()
+        .filter(|part| matches!(part, SnaxExpressionPart::Group(_)))
+        .collect();
+
+    assert_eq!(groups.len(), 1);
+}
+
+#[test]
+fn markup_after_arrows_and_commas() {
+    let item = snax::parse_embedded(quote!(
+        <div>
+            { match user { Some(user) => <b>{ user }</b>, None => <i /> } }
+            { vec![<a />, <>"b"</>] }
+            { || <br /> }
+            { if ok { <p /> } else { <hr /> } }
+        </div>
+    )).unwrap();
+
+    let counts: Vec<_> = item.children().iter()
+        .map(|child| expression(child).items().len())
+        .collect();
+
+    assert_eq!(counts, vec![2, 2, 1, 2]);
+}
+
+#[test]
+fn comparisons_are_left_alone() {
+    let input = quote!(
+        <div>
+            { a < b }
+            { Vec::<u8>::new() }
+            { x.len() as usize < 5 }
+        </div>
+    );
+
+    assert_eq!(snax::parse_embedded(input.clone()).unwrap(), snax::parse(input).unwrap());
+}
+
+#[test]
+fn qualified_paths_are_left_alone() {
+    let input = quote!(
+        <div>
+            { <T as Default>::default() }
+            { <Vec<u8>>::new() }
+            { <fn() -> u8 as Clone>::clone }
+        </div>
+    );
+
+    assert_eq!(snax::parse_embedded(input.clone()).unwrap(), snax::parse(input).unwrap());
+
+    let item = snax::parse_embedded(quote!(
+        <div>{ <Option<u8>>::None.map(|n| <b>{ n }</b>) }</div>
+    )).unwrap();
+
+    assert_eq!(expression(&item.children()[0]).items().len(), 1);
+}
+
+#[test]
+fn markup_in_attributes() {
+    let item = snax::parse_embedded(quote!(
+        <Table row={ |user| <Row user={ user } /> } class={ "a" } />
+    )).unwrap();
+
+    let attributes = match &item {
+        SnaxItem::SelfClosingTag(tag) => &tag.attributes,
+        other => panic!("expected a self-closing tag, got {:?}", other),
+    };
+
+    match &attributes[0] {
+        SnaxAttribute::Expression { name, value } => {
+            assert_eq!(name.to_string(), "row");
+            assert_eq!(value.items().len(), 1);
+        },
+        other => panic!("expected an expression attribute, got {:?}", other),
+    }
+
+    match &attributes[1] {
+        SnaxAttribute::Simple { .. } => {},
+        other => panic!("expected a simple attribute, got {:?}", other),
+    }
+}
+
+#[test]
+fn markup_inside_markup_attributes() {
+    let item = snax::parse_embedded(quote!(
+        <Layout header=<ul>{ xs.map(|x| <li>{ x }</li>) }</ul> />
+    )).unwrap();
+
+    let header = match &item {
+        SnaxItem::SelfClosingTag(tag) => match &tag.attributes[0] {
+            SnaxAttribute::Markup { value, .. } => value,
+            other => panic!("expected a markup attribute, got {:?}", other),
+        },
+        other => panic!("expected a self-closing tag, got {:?}", other),
+    };
+
+    assert_eq!(expression(&header.children()[0]).items().len(), 1);
+}
+
+#[test]
+fn substitute_replaces_items() {
+    let item = snax::parse_embedded(quote!(
+        <p>{ items.map(|item| <b>{ item }</b>).unwrap_or(<i />) }</p>
+    )).unwrap();
+
+    let mut count = 0u32;
+    let substituted = expression(&item.children()[0]).substitute(|_| {
+        count += 1;
+        let name = quote::format_ident!("item{}", count);
+        quote!(#name)
+    });
+
+    let expected: TokenTree = quote!({ items.map(|item| item1).unwrap_or(item2) })
+        .into_iter()
+        .next()
+        .unwrap();
+
+    assert_eq!(substituted.to_string(), expected.to_string());
+}
+
+#[test]
+fn plain_parse_ignores_markup_in_blocks() {
+    let item = snax::parse(quote!(<ul>{ xs.map(|x| <li />) }</ul>)).unwrap();
+
+    match &item.children()[0] {
+        SnaxItem::Content(_) => {},
+        other => panic!("expected content, got {:?}", other),
+    }
+}
+
+#[test]
+fn errors_in_embedded_markup() {
+    let error = snax::parse_embedded(quote!(<ul>{ xs.map(|x| <li>) }</ul>)).unwrap_err();
+    assert_eq!(error.to_string(), "input ended before closing `<li>`");
+}
@@ -0,0 +1,103 @@
+use quote::quote;
+
+use snax::html::{render_static, Dynamic};
+
+fn render(input: proc_macro2::TokenStream) -> Result<String, Dynamic> {
+    render_static(&snax::parse(input).unwrap())
+}
+
+#[test]
+fn renders_tags() {
+    let output = render(quote!(
+        <div class="card" hidden>
+            <h1>"Title"</h1>
+            <img src="a.png" alt="" />
+            <span data-count=3 />
+        </div>
+    )).unwrap();
+
+    assert_eq!(
+        output,
+        r#"<div class="card" hidden><h1>Title</h1><img src="a.png" alt=""><span data-count="3"></span></div>"#,
+    );
+}
+
+#[test]
+fn renders_unbraced_attribute_values() {
+    let output = render(quote!(<input checked=true disabled=false min=-1 />)).unwrap();
+    assert_eq!(output, r#"<input checked min="-1">"#);
+
+    match render(quote!(<i class=Icon::CLASS />)) {
+        Err(Dynamic::Value { .. }) => {},
+        other => panic!("expected Dynamic::Value, got {:?}", other),
+    }
+}
+
+#[test]
+fn renders_numbers_without_separators_or_suffixes() {
+    let output = render(quote!(
+        <data max=1_000 min=-5i32 mask=0xff_u8 ratio=1_000.5f32>5u8 0b101 1e3</data>
+    )).unwrap();
+
+    assert_eq!(output, r#"<data max="1000" min="-5" mask="255" ratio="1000.5">551e3</data>"#);
+}
+
+#[test]
+fn flattens_fragments() {
+    let output = render(quote!(
+        <>
+            <li>"a"</li>
+            <><li>"b"</li></>
+        </>
+    )).unwrap();
+
+    assert_eq!(output, "<li>a</li><li>b</li>");
+}
+
+#[test]
+fn escapes_and_decodes_literals() {
+    let output: proc_macro2::TokenStream = r##"
+        <p title="say \"hi\" & <go>">"a < b\n" r#""raw""# 'c' "\u{e9}"</p>
+    "##.parse().unwrap();
+
+    assert_eq!(
+        render(output).unwrap(),
+        "<p title=\"say &quot;hi&quot; &amp; &lt;go&gt;\">a &lt; b\n\"raw\"c\u{e9}</p>",
+    );
+}
+
+#[test]
+fn rejects_dynamic_parts() {
+    match render(quote!(<p>{ name }</p>)) {
+        Err(Dynamic::Value { .. }) => {},
+        other => panic!("expected Dynamic::Value, got {:?}", other),
+    }
+
+    match render(quote!(<p class={ class }></p>)) {
+        Err(Dynamic::Value { .. }) => {},
+        other => panic!("expected Dynamic::Value, got {:?}", other),
+    }
+
+    match render(quote!(<p {..props} />)) {
+        Err(Dynamic::Spread { .. }) => {},
+        other => panic!("expected Dynamic::Spread, got {:?}", other),
+    }
+
+    match render(quote!(<ui::Button />)) {
+        Err(Dynamic::Component { .. }) => {},
+        other => panic!("expected Dynamic::Component, got {:?}", other),
+    }
+
+    match render(quote!(<List::<u32>></List>)) {
+        Err(Dynamic::Component { .. }) => {},
+        other => panic!("expected Dynamic::Component, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_control_flow() {
+    match render(quote!(<ul>@for x in xs { <li /> }</ul>)) {
+        Err(Dynamic::ControlFlow { .. }) => {},
+        other => panic!("expected Dynamic::ControlFlow, got {:?}", other),
+    }
+}
@@ -0,0 +1,227 @@
+use quote::quote;
+
+use snax::ir::{self, Attribute, Element, HoleKind, Node, Template};
+
+fn lower(input: proc_macro2::TokenStream) -> Template {
+    ir::lower(&snax::parse(input).unwrap())
+}
+
+#[test]
+fn fully_static_tree_is_hoisted() {
+    let template = lower(quote!(
+        <ul class="menu">
+            <li>"a" "b"</li>
+            <><li hidden /></>
+        </ul>
+    ));
+
+    assert!(template.holes.is_empty());
+    assert_eq!(template.nodes, vec![Node::Static(0)]);
+    assert_eq!(template.statics.len(), 1);
+    assert_eq!(template.statics[0].children[0], Node::Element(Element {
+        name: "li".to_string(),
+        attributes: Vec::new(),
+        children: vec![Node::Text("ab".to_string())],
+    }));
+    assert_eq!(template.to_html(), r#"<ul class="menu"><li>ab</li><li hidden></li></ul>"#);
+}
+
+#[test]
+fn holes_have_stable_indices_and_paths() {
+    let template = lower(quote!(
+        <div id="root" class={ class }>
+            <header><h1>"Static"</h1></header>
+            "Hello, " { name }
+            <input {..props} value={ value } disabled />
+        </div>
+    ));
+
+    let paths: Vec<_> = template.holes.iter().map(|hole| hole.path.clone()).collect();
+    assert_eq!(paths, vec![vec![0], vec![0, 2], vec![0, 3], vec![0, 3]]);
+
+    match &template.holes[0].kind {
+        HoleKind::Attribute { name, value } => {
+            assert_eq!(name, "class");
+            assert_eq!(value.to_string(), "class");
+        },
+        other => panic!("expected an attribute hole, got {:?}", other),
+    }
+
+    match &template.holes[1].kind {
+        HoleKind::Content { value } => assert_eq!(value.to_string(), "name"),
+        other => panic!("expected a content hole, got {:?}", other),
+    }
+
+    let root = match &template.nodes[0] {
+        Node::Element(root) => root,
+        other => panic!("expected an element, got {:?}", other),
+    };
+
+    assert_eq!(root.attributes[1], Attribute::Dynamic { name: "class".to_string(), hole: 0 });
+    assert_eq!(root.children[0], Node::Static(0));
+    assert_eq!(root.children[1], Node::Text("Hello, ".to_string()));
+    assert_eq!(root.children[2], Node::Hole(1));
+
+    match &root.children[3] {
+        Node::Element(input) => assert_eq!(input.attributes, vec![
+            Attribute::Spread { hole: 2 },
+            Attribute::Dynamic { name: "value".to_string(), hole: 3 },
+            Attribute::Static { name: "disabled".to_string(), value: None },
+        ]),
+        other => panic!("expected an element, got {:?}", other),
+    }
+
+    assert_eq!(
+        template.to_html(),
+        r#"<div id="root"><header><h1>Static</h1></header>Hello, <!----><input disabled></div>"#,
+    );
+}
+
+#[test]
+fn components_are_holes() {
+    let template = lower(quote!(
+        <main>
+            <ui::Card title="x">
+                <p>{ body }</p>
+            </ui::Card>
+            <List::<u32> />
+        </main>
+    ));
+
+    assert_eq!(template.holes.len(), 2);
+    assert_eq!(template.holes[0].path, vec![0, 0]);
+    assert_eq!(template.holes[1].path, vec![0, 1]);
+
+    match &template.holes[0].kind {
+        HoleKind::Component { name, attributes, children, .. } => {
+            assert_eq!(name.to_string(), "ui::Card");
+            assert_eq!(attributes.len(), 1);
+            assert_eq!(children.holes.len(), 1);
+            assert_eq!(children.holes[0].path, vec![0, 0]);
+            assert_eq!(children.to_html(), "<p><!----></p>");
+        },
+        other => panic!("expected a component hole, got {:?}", other),
+    }
+
+    match &template.holes[1].kind {
+        HoleKind::Component { generics, .. } => {
+            assert_eq!(generics.as_ref().unwrap().to_string(), "u32");
+        },
+        other => panic!("expected a component hole, got {:?}", other),
+    }
+}
+
+#[test]
+fn control_flow_is_a_hole_with_nested_templates() {
+    let template = lower(quote!(
+        <ul>
+            @for user in users {
+                <li class="user">{ user }</li>
+            }
+            @if admin { <hr /> } else { "guest" }
+        </ul>
+    ));
+
+    assert_eq!(template.holes.len(), 2);
+    assert_eq!(template.holes[0].path, vec![0, 0]);
+    assert_eq!(template.holes[1].path, vec![0, 1]);
+    assert_eq!(template.to_html(), "<ul><!----><!----></ul>");
+
+    match &template.holes[0].kind {
+        HoleKind::For { pattern, iterator, body } => {
+            assert_eq!(pattern.to_string(), "user");
+            assert_eq!(iterator.to_string(), "users");
+            assert_eq!(body.holes.len(), 1);
+            assert_eq!(body.to_html(), r#"<li class="user"><!----></li>"#);
+        },
+        other => panic!("expected a for hole, got {:?}", other),
+    }
+
+    match &template.holes[1].kind {
+        HoleKind::If { condition, then_branch, else_branch } => {
+            assert_eq!(condition.to_string(), "admin");
+            assert_eq!(then_branch.to_html(), "<hr>");
+            assert_eq!(else_branch.as_ref().unwrap().to_html(), "guest");
+        },
+        other => panic!("expected an if hole, got {:?}", other),
+    }
+}
+
+#[test]
+fn embedded_markup_is_a_hole_with_templates() {
+    let template = ir::lower(&snax::parse_embedded(quote!(
+        <ul class={ |x| <b /> }>
+            { users.iter().map(|user| <li class="user">{ user }</li>) }
+        </ul>
+    )).unwrap());
+
+    assert_eq!(template.holes.len(), 2);
+    assert_eq!(template.to_html(), "<ul><!----></ul>");
+
+    match &template.holes[0].kind {
+        HoleKind::Expression { name, templates, .. } => {
+            assert_eq!(name.as_deref(), Some("class"));
+            assert_eq!(templates[0].to_html(), "<b></b>");
+        },
+        other => panic!("expected an expression hole, got {:?}", other),
+    }
+
+    match &template.holes[1].kind {
+        HoleKind::Expression { name, expression, templates } => {
+            assert_eq!(*name, None);
+            assert_eq!(expression.items().len(), 1);
+            assert_eq!(templates[0].to_html(), r#"<li class="user"><!----></li>"#);
+        },
+        other => panic!("expected an expression hole, got {:?}", other),
+    }
+}
+
+#[test]
+fn markup_attributes_are_holes_with_templates() {
+    let template = lower(quote!(<div title=<b>"x"</b>></div>));
+
+    assert_eq!(template.to_html(), "<div></div>");
+
+    match &template.holes[0].kind {
+        HoleKind::Markup { name, template } => {
+            assert_eq!(name, "title");
+            assert_eq!(template.to_html(), "<b>x</b>");
+        },
+        other => panic!("expected a markup hole, got {:?}", other),
+    }
+}
+
+#[test]
+fn unbraced_attribute_values() {
+    let template = lower(quote!(<input checked=true disabled=false min=-1 name=Field::NAME />));
+
+    assert_eq!(template.to_html(), r#"<input checked min="-1">"#);
+
+    match &template.holes[0].kind {
+        HoleKind::Attribute { name, value } => {
+            assert_eq!(name, "name");
+            assert_eq!(value.to_string(), quote!(Field::NAME).to_string());
+        },
+        other => panic!("expected an attribute hole, got {:?}", other),
+    }
+}
+
+#[test]
+fn numbers_are_written_as_displayed() {
+    let template = lower(quote!(<input max=1_000 step=5u8 mask=0x1f />));
+
+    assert_eq!(template.to_html(), r#"<input max="1000" step="5" mask="31">"#);
+}
+
+#[test]
+fn empty_text_is_dropped() {
+    let template = lower(quote!(<p>"" { x } "" "a"</p>));
+
+    assert_eq!(template.to_html(), "<p><!---->a</p>");
+    assert_eq!(template.holes[0].path, vec![0, 0]);
+
+    match &template.nodes[0] {
+        Node::Element(p) => assert_eq!(p.children, vec![Node::Hole(0), Node::Text("a".to_string())]),
+        other => panic!("expected an element, got {:?}", other),
+    }
+}
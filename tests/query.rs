@@ -0,0 +1,202 @@
+use quote::quote;
+
+use snax::{SelectorError, SnaxItem};
+
+fn names<'a>(items: impl Iterator<Item = &'a SnaxItem>) -> Vec<String> {
+    items
+        .map(|item| match item.name() {
+            Some(name) => name.to_string(),
+            None => match item {
+                SnaxItem::Fragment(_) => "<>".to_string(),
+                _ => "content".to_string(),
+            },
+        })
+        .collect()
+}
+
+#[test]
+fn children_of_tags_and_fragments() {
+    let tag = snax::parse(quote!(<ul><li /><li /></ul>)).unwrap();
+    assert_eq!(tag.children().len(), 2);
+
+    let fragment = snax::parse(quote!(<><br /></>)).unwrap();
+    assert_eq!(fragment.children().len(), 1);
+
+    let self_closing = snax::parse(quote!(<br />)).unwrap();
+    assert!(self_closing.children().is_empty());
+}
+
+#[test]
+fn descendants_are_pre_order() {
+    let item = snax::parse(quote!(
+        <div>
+            <header><h1 /></header>
+            <>
+                <p><span /></p>
+            </>
+            <footer />
+        </div>
+    )).unwrap();
+
+    assert_eq!(
+        names(item.descendants()),
+        vec!["header", "h1", "<>", "p", "span", "footer"],
+    );
+}
+
+#[test]
+fn find_by_name_includes_self() {
+    let item = snax::parse(quote!(
+        <Button>
+            <ui::Button />
+            <div><Button /></div>
+        </Button>
+    )).unwrap();
+
+    assert_eq!(item.find_by_name("Button").count(), 2);
+    assert_eq!(item.find_by_name("ui::Button").count(), 1);
+    assert_eq!(item.find_by_name("span").count(), 0);
+}
+
+#[test]
+fn attribute_lookup() {
+    let item = snax::parse(quote!(
+        <div class="a" data-id={ 5 } hidden {..props}>
+            <img src="x.png" />
+        </div>
+    )).unwrap();
+
+    let tag = match &item {
+        SnaxItem::Tag(tag) => tag,
+        _ => panic!("expected a tag, got {:?}", item),
+    };
+
+    assert_eq!(tag.attribute("class").unwrap().to_string(), "\"a\"");
+    assert_eq!(tag.attribute("data-id").unwrap().to_string(), quote!({ 5 }).to_string());
+    assert!(tag.attribute("hidden").is_none());
+    assert!(tag.attribute("props").is_none());
+
+    let image = match &tag.children[0] {
+        SnaxItem::SelfClosingTag(image) => image,
+        other => panic!("expected a self-closing tag, got {:?}", other),
+    };
+
+    assert_eq!(image.attribute("src").unwrap().to_string(), "\"x.png\"");
+}
+
+fn select_names(item: &SnaxItem, selector: &str) -> Vec<String> {
+    snax::select(item, selector).unwrap()
+        .into_iter()
+        .map(|item| {
+            let key = match item {
+                SnaxItem::Tag(tag) => tag.attribute("key"),
+                SnaxItem::SelfClosingTag(tag) => tag.attribute("key"),
+                _ => None,
+            };
+
+            match key {
+                Some(key) => format!("{}[{}]", item.name().unwrap(), key),
+                None => item.name().unwrap().to_string(),
+            }
+        })
+        .collect()
+}
+
+fn list() -> SnaxItem {
+    snax::parse(quote!(
+        <main>
+            <ul class="menu">
+                "heading"
+                <li key="a"><a href="/a" /></li>
+                <>
+                    <li key="b" disabled />
+                </>
+                <li><ui::Icon size=2 /></li>
+            </ul>
+            <li key="c" />
+        </main>
+    )).unwrap()
+}
+
+#[test]
+fn select_by_tag() {
+    let item = list();
+
+    assert_eq!(select_names(&item, "li"), vec![r#"li["a"]"#, r#"li["b"]"#, "li", r#"li["c"]"#]);
+    assert_eq!(select_names(&item, "ui::Icon"), vec!["ui::Icon"]);
+    assert_eq!(select_names(&item, "main").len(), 1);
+    assert_eq!(select_names(&item, "*").len(), 8);
+}
+
+#[test]
+fn select_by_attribute() {
+    let item = list();
+
+    assert_eq!(select_names(&item, "[disabled]"), vec![r#"li["b"]"#]);
+    assert_eq!(select_names(&item, "li[key=b]"), vec![r#"li["b"]"#]);
+    assert_eq!(select_names(&item, r#"li[key="c"]"#), vec![r#"li["c"]"#]);
+    assert_eq!(select_names(&item, "[size=2]"), vec!["ui::Icon"]);
+    assert_eq!(select_names(&item, "ul[class='menu'][key]"), Vec::<String>::new());
+}
+
+#[test]
+fn select_with_combinators() {
+    let item = list();
+
+    assert_eq!(select_names(&item, "ul > li[key]"), vec![r#"li["a"]"#, r#"li["b"]"#]);
+    assert_eq!(select_names(&item, "main li > a"), vec!["a"]);
+    assert_eq!(select_names(&item, "main>li"), vec![r#"li["c"]"#]);
+    assert_eq!(select_names(&item, "main ui::Icon"), vec!["ui::Icon"]);
+    assert_eq!(select_names(&item, "ul ul"), Vec::<String>::new());
+}
+
+#[test]
+fn select_first_child() {
+    let item = list();
+
+    assert_eq!(select_names(&item, "li:first-child"), vec![r#"li["a"]"#]);
+    assert_eq!(select_names(&item, "main > :first-child"), vec!["ul"]);
+    assert_eq!(select_names(&item, ":first-child > :first-child"), vec!["ul", r#"li["a"]"#, "a"]);
+    assert_eq!(select_names(&item, "main:first-child"), vec!["main"]);
+}
+
+#[test]
+fn select_errors() {
+    let item = list();
+
+    assert_eq!(
+        snax::select(&item, "li:last-child").unwrap_err(),
+        SelectorError::UnsupportedPseudoClass("last-child".to_string()),
+    );
+    assert_eq!(
+        snax::select(&item, "li[key").unwrap_err(),
+        SelectorError::UnexpectedEnd { expected: "`]` or `=`" },
+    );
+    assert_eq!(
+        snax::select(&item, "ul >").unwrap_err(),
+        SelectorError::UnexpectedEnd { expected: "a tag name, `*`, `[`, or `:`" },
+    );
+    assert_eq!(
+        snax::select(&item, "ul, li").unwrap_err(),
+        SelectorError::UnexpectedChar { found: ',', position: 2, expected: "a combinator" },
+    );
+}
+
+#[test]
+fn control_flow_is_transparent() {
+    let item = snax::parse(quote!(
+        <ul>
+            @if a { <li key="a" /> } else { <li key="b" /> }
+            @for x in xs { <li key="c"><b /></li> }
+            @match x { _ => <li key="d" /> }
+        </ul>
+    )).unwrap();
+
+    assert_eq!(item.children().len(), 3);
+    assert_eq!(item.descendants().filter(|item| item.name().is_some()).count(), 5);
+    assert_eq!(
+        select_names(&item, "ul > li:first-child"),
+        vec![r#"li["a"]"#, r#"li["b"]"#],
+    );
+    assert_eq!(select_names(&item, "li > b"), vec!["b"]);
+}
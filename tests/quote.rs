@@ -0,0 +1,101 @@
+#![cfg(feature = "quote")]
+
+use quote::{quote, ToTokens};
+
+fn assert_round_trip(input: proc_macro2::TokenStream) {
+    let parsed = snax::parse(input).unwrap();
+    let printed = parsed.to_token_stream();
+    let reparsed = snax::parse(printed.clone())
+        .unwrap_or_else(|error| panic!("could not reparse `{}`: {}", printed, error));
+
+    assert_eq!(parsed, reparsed);
+}
+
+#[test]
+fn round_trip_tags() {
+    assert_round_trip(quote!(
+        <div class="a" hidden {..props}>
+            <span>"Hello" { name }</span>
+            <br />
+            5
+        </div>
+    ));
+}
+
+#[test]
+fn round_trip_fragments() {
+    assert_round_trip(quote!(
+        <>
+            <li />
+            <></>
+        </>
+    ));
+}
+
+#[test]
+fn round_trip_names() {
+    assert_round_trip(quote!(
+        <my-widget data-id="1" xlink:href="#a">
+            <ui::Button />
+            <crate::List::<Vec<User>> items={users}></crate::List>
+        </my-widget>
+    ));
+}
+
+#[test]
+fn round_trip_unbraced_attribute_values() {
+    assert_round_trip(quote!(
+        <Slider snap=true open=false min=-1 kind=IconKind::Close value=value />
+    ));
+}
+
+#[test]
+fn round_trip_shorthand_attributes() {
+    assert_round_trip(quote!(<Input {value} {..rest} />));
+}
+
+#[test]
+fn printed_markup() {
+    let item = snax::parse(quote!(<a href="/">"Home"</a>)).unwrap();
+
+    assert_eq!(item.to_token_stream().to_string(), r#"< a href = "/" > "Home" </ a >"#);
+}
+
+#[test]
+fn round_trip_control_flow() {
+    assert_round_trip(quote!(
+        <ul>
+            @for (index, user) in users.iter().enumerate() {
+                <li>{ index }</li>
+            }
+            @if users.is_empty() { "none" } else if x { <br /> } else { <hr /> }
+            @match status {
+                Status::Ok(value) if value > 0 => <a />,
+                _ => { "error" }
+            }
+        </ul>
+    ));
+}
+
+#[test]
+fn round_trip_embedded_markup() {
+    let input = quote!(
+        <ul row={ |x| <Row x={ x } /> }>
+            { users.iter().map(|user| <li>{ user }</li>) }
+        </ul>
+    );
+
+    let parsed = snax::parse_embedded(input).unwrap();
+    let reparsed = snax::parse_embedded(parsed.to_token_stream()).unwrap();
+
+    assert_eq!(parsed, reparsed);
+}
+
+#[test]
+fn round_trip_markup_attributes() {
+    assert_round_trip(quote!(
+        <Layout header=<Header title="Home" /> footer=<>"x"</>>
+            <p />
+        </Layout>
+    ));
+}
@@ -0,0 +1,61 @@
+use proc_macro2::{LineColumn, Span, TokenStream};
+
+use snax::SnaxItem;
+
+fn parse_str(source: &str) -> SnaxItem {
+    let input: TokenStream = source.parse().unwrap();
+    snax::parse(input).unwrap()
+}
+
+fn range(span: Span) -> ((usize, usize), (usize, usize)) {
+    let LineColumn { line: start_line, column: start_column } = span.start();
+    let LineColumn { line: end_line, column: end_column } = span.end();
+
+    ((start_line, start_column), (end_line, end_column))
+}
+
+#[test]
+fn tag_spans() {
+    let item = parse_str("<div class=\"a\">\n    <br />\n</div>");
+
+    let tag = match &item {
+        SnaxItem::Tag(tag) => tag,
+        _ => panic!("expected a tag, got {:?}", item),
+    };
+
+    assert_eq!(range(tag.open_span), ((1, 0), (1, 15)));
+    assert_eq!(range(tag.close_span), ((3, 0), (3, 6)));
+    assert_eq!(range(tag.span()), ((1, 0), (3, 6)));
+    assert_eq!(range(item.span()), ((1, 0), (3, 6)));
+
+    assert_eq!(range(tag.attributes[0].span()), ((1, 5), (1, 14)));
+    assert_eq!(range(tag.children[0].span()), ((2, 4), (2, 10)));
+}
+
+#[test]
+fn fragment_spans() {
+    let item = parse_str("<>\n    \"hi\"\n</>");
+
+    let fragment = match &item {
+        SnaxItem::Fragment(fragment) => fragment,
+        _ => panic!("expected a fragment, got {:?}", item),
+    };
+
+    assert_eq!(range(fragment.open_span), ((1, 0), (1, 2)));
+    assert_eq!(range(fragment.close_span), ((3, 0), (3, 3)));
+    assert_eq!(range(fragment.children[0].span()), ((2, 4), (2, 8)));
+}
+
+#[test]
+fn attribute_spans() {
+    let item = parse_str("<input disabled {..props} />");
+
+    let tag = match &item {
+        SnaxItem::SelfClosingTag(tag) => tag,
+        _ => panic!("expected a self-closing tag, got {:?}", item),
+    };
+
+    assert_eq!(range(tag.span()), ((1, 0), (1, 28)));
+    assert_eq!(range(tag.attributes[0].span()), ((1, 7), (1, 15)));
+    assert_eq!(range(tag.attributes[1].span()), ((1, 16), (1, 25)));
+}
@@ -0,0 +1,326 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use snax::codegen::ssr::{self, LowerError, Markup, Raw, Render, RenderAttributes, RenderMarkup};
+
+fn lower(input: TokenStream) -> Result<String, LowerError> {
+    ssr::lower(&snax::parse(input).unwrap(), quote!(&mut output)).map(|code| code.to_string())
+}
+
+/// Builds the code generated around a list of calls.
+fn expected(calls: TokenStream) -> String {
+    quote!({
+        let __snax_writer: &mut dyn ::core::fmt::Write = &mut output;
+        '__snax: {
+            #calls
+            ::core::result::Result::Ok(())
+        }
+    }).to_string()
+}
+
+#[test]
+fn merges_static_markup() {
+    let output = lower(quote!(
+        <div class="a" hidden>
+            <><img src="x.png" /></>
+            "Fish & chips"
+            <span />
+        </div>
+    )).unwrap();
+
+    assert_eq!(output, expected(quote!(
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(
+            &mut *__snax_writer,
+            "<div class=\"a\" hidden><img src=\"x.png\">Fish &amp; chips<span></span></div>"
+        ) {
+            break '__snax ::core::result::Result::Err(error);
+        }
+    )));
+}
+
+#[test]
+fn renders_dynamic_parts() {
+    let output = lower(quote!(
+        <p title={ title } {..extra}>{ name }</p>
+    )).unwrap();
+
+    assert_eq!(output, expected(quote!(
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<p title=\"") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(title), &mut *__snax_writer) {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "\"") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::RenderAttributes::render_attributes(&(extra), &mut *__snax_writer) {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, ">") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(name), &mut *__snax_writer) {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</p>") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+    )));
+}
+
+#[test]
+fn renders_unbraced_attribute_values() {
+    let output = lower(quote!(
+        <input checked=true disabled=false min=-1 name=Field::NAME />
+    )).unwrap();
+
+    assert_eq!(output, expected(quote!(
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<input checked min=\"-1\" name=\"") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(Field::NAME), &mut *__snax_writer) {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "\">") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+    )));
+}
+
+#[test]
+fn control_flow_becomes_rust() {
+    let output = lower(quote!(<p>@if ok { "yes" } else { { n } }</p>)).unwrap();
+
+    assert_eq!(output, expected(quote!(
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<p>") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        if ok {
+            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "yes") {
+                break '__snax ::core::result::Result::Err(error);
+            }
+        } else {
+            if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(n), &mut *__snax_writer) {
+                break '__snax ::core::result::Result::Err(error);
+            }
+        }
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</p>") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+    )));
+}
+
+#[test]
+fn rejects_components() {
+    match lower(quote!(<div><ui::Button /></div>)) {
+        Err(LowerError::Component { name, .. }) => assert_eq!(name.to_string(), "ui::Button"),
+        other => panic!("expected LowerError::Component, got {:?}", other),
+    }
+}
+
+#[test]
+fn embedded_markup_becomes_closures() {
+    let item = snax::parse_embedded(quote!(<p>{ x.map(|x| <b />) }</p>)).unwrap();
+    let output = ssr::lower(&item, quote!(&mut output)).unwrap().to_string();
+
+    assert_eq!(output, expected(quote!(
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<p>") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+        {
+            let __snax_markup = { x.map(|x| ::snax::codegen::ssr::Markup::new(
+                |__snax_writer: &mut dyn ::core::fmt::Write| -> ::core::fmt::Result {
+                    '__snax: {
+                        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<b></b>") {
+                            break '__snax ::core::result::Result::Err(error);
+                        }
+                        ::core::result::Result::Ok(())
+                    }
+                }
+            )) };
+            if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::RenderMarkup::render_markup(__snax_markup, &mut *__snax_writer) {
+                break '__snax ::core::result::Result::Err(error);
+            }
+        }
+        if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</p>") {
+            break '__snax ::core::result::Result::Err(error);
+        }
+    )));
+}
+
+#[test]
+fn rejects_markup_in_attributes() {
+    let item = snax::parse_embedded(quote!(<div title={ <b /> }></div>)).unwrap();
+
+    match ssr::lower(&item, quote!(&mut output)) {
+        Err(LowerError::MarkupAttribute { name, .. }) => assert_eq!(name.to_string(), "title"),
+        other => panic!("expected LowerError::MarkupAttribute, got {:?}", other),
+    }
+
+    match lower(quote!(<div title=<b /> />)) {
+        Err(LowerError::MarkupAttribute { name, .. }) => assert_eq!(name.to_string(), "title"),
+        other => panic!("expected LowerError::MarkupAttribute, got {:?}", other),
+    }
+}
+
+/// Defines a function whose body is generated code, so that the compiler checks
+/// it, along with a constant holding the same code to compare against.
+macro_rules! compiled {
+    (fn $name:ident($($params:tt)*) $body:tt as $source:ident) => {
+        fn $name($($params)*) -> ::core::fmt::Result $body
+        const $source: &str = stringify!($body);
+    };
+}
+
+compiled! {
+    fn render_list(output: &mut String, class: &str, items: &[&str], first: Option<&str>) {
+        let __snax_writer: &mut dyn ::core::fmt::Write = output;
+        '__snax: {
+            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<p class=\"") {
+                break '__snax ::core::result::Result::Err(error);
+            }
+            if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(class), &mut *__snax_writer) {
+                break '__snax ::core::result::Result::Err(error);
+            }
+            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "\">") {
+                break '__snax ::core::result::Result::Err(error);
+            }
+            for item in items {
+                if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<b>") {
+                    break '__snax ::core::result::Result::Err(error);
+                }
+                if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(item), &mut *__snax_writer) {
+                    break '__snax ::core::result::Result::Err(error);
+                }
+                if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</b>") {
+                    break '__snax ::core::result::Result::Err(error);
+                }
+            }
+            {
+                let __snax_markup = { first.map(|first| ::snax::codegen::ssr::Markup::new(
+                    |__snax_writer: &mut dyn ::core::fmt::Write| -> ::core::fmt::Result {
+                        '__snax: {
+                            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<i>") {
+                                break '__snax ::core::result::Result::Err(error);
+                            }
+                            if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(first), &mut *__snax_writer) {
+                                break '__snax ::core::result::Result::Err(error);
+                            }
+                            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</i>") {
+                                break '__snax ::core::result::Result::Err(error);
+                            }
+                            ::core::result::Result::Ok(())
+                        }
+                    }
+                )) };
+                if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::RenderMarkup::render_markup(__snax_markup, &mut *__snax_writer) {
+                    break '__snax ::core::result::Result::Err(error);
+                }
+            }
+            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</p>") {
+                break '__snax ::core::result::Result::Err(error);
+            }
+            ::core::result::Result::Ok(())
+        }
+    } as RENDER_LIST
+}
+
+#[test]
+fn generated_code_compiles_and_runs() {
+    let item = snax::parse_embedded(quote!(
+        <p class={ class }>
+            @for item in items { <b>{ item }</b> }
+            { first.map(|first| <i>{ first }</i>) }
+        </p>
+    )).unwrap();
+
+    let generated = ssr::lower(&item, quote!(output)).unwrap();
+    let source: TokenStream = RENDER_LIST.parse().unwrap();
+    assert_eq!(generated.to_string(), source.to_string());
+
+    let mut output = String::new();
+    render_list(&mut output, "a&b", &["x", "<y>"], Some("z")).unwrap();
+    assert_eq!(output, r#"<p class="a&amp;b"><b>x</b><b>&lt;y&gt;</b><i>z</i></p>"#);
+}
+
+compiled! {
+    fn render_titled(output: &mut String, title: String, items: &[u32]) {
+        let __snax_writer: &mut dyn ::core::fmt::Write = output;
+        '__snax: {
+            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<ul>") {
+                break '__snax ::core::result::Result::Err(error);
+            }
+            {
+                let __snax_markup = { items.iter().map(|item| ::snax::codegen::ssr::Markup::new(
+                    |__snax_writer: &mut dyn ::core::fmt::Write| -> ::core::fmt::Result {
+                        '__snax: {
+                            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "<li>") {
+                                break '__snax ::core::result::Result::Err(error);
+                            }
+                            if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(title), &mut *__snax_writer) {
+                                break '__snax ::core::result::Result::Err(error);
+                            }
+                            if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::Render::render(&(item), &mut *__snax_writer) {
+                                break '__snax ::core::result::Result::Err(error);
+                            }
+                            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</li>") {
+                                break '__snax ::core::result::Result::Err(error);
+                            }
+                            ::core::result::Result::Ok(())
+                        }
+                    }
+                )) };
+                if let ::core::result::Result::Err(error) = ::snax::codegen::ssr::RenderMarkup::render_markup(__snax_markup, &mut *__snax_writer) {
+                    break '__snax ::core::result::Result::Err(error);
+                }
+            }
+            if let ::core::result::Result::Err(error) = ::core::fmt::Write::write_str(&mut *__snax_writer, "</ul>") {
+                break '__snax ::core::result::Result::Err(error);
+            }
+            ::core::result::Result::Ok(())
+        }
+    } as RENDER_TITLED
+}
+
+#[test]
+fn generated_code_borrows_owned_values() {
+    let item = snax::parse_embedded(quote!(
+        <ul>{ items.iter().map(|item| <li>{ title }{ item }</li>) }</ul>
+    )).unwrap();
+
+    let generated = ssr::lower(&item, quote!(output)).unwrap();
+    let source: TokenStream = RENDER_TITLED.parse().unwrap();
+    assert_eq!(generated.to_string(), source.to_string());
+
+    let mut output = String::new();
+    render_titled(&mut output, "#".to_string(), &[1, 2]).unwrap();
+    assert_eq!(output, "<ul><li>#1</li><li>#2</li></ul>");
+}
+
+#[test]
+fn runtime_markup() {
+    let mut output = String::new();
+    let name = "<a>";
+
+    Markup::new(|writer| writer.write_str("<b>")).render_markup(&mut output).unwrap();
+    Some(Markup::new(|writer| name.render(writer))).render_markup(&mut output).unwrap();
+    (0..2).map(|_| Markup::new(|writer| writer.write_str("<br>"))).render_markup(&mut output).unwrap();
+
+    assert_eq!(output, "<b>&lt;a&gt;<br><br>");
+}
+
+#[test]
+fn runtime_escaping() {
+    let mut output = String::new();
+
+    "<a href=\"x\">&".render(&mut output).unwrap();
+    Raw("<br>").render(&mut output).unwrap();
+    [("id", 5), ("title", 6)].render_attributes(&mut output).unwrap();
+    vec![("alt", "\"hi\"")].render_attributes(&mut output).unwrap();
+
+    assert_eq!(
+        output,
+        "&lt;a href=&quot;x&quot;&gt;&amp;<br> id=\"5\" title=\"6\" alt=\"&quot;hi&quot;\"",
+    );
+}
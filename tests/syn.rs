@@ -0,0 +1,81 @@
+#![cfg(feature = "syn")]
+
+use proc_macro2::Ident;
+use quote::quote;
+use syn::{
+    Token,
+    parse::{Parse, ParseStream, Parser},
+};
+
+use snax::{
+    SnaxAttribute,
+    SnaxItem,
+    SnaxSelfClosingTag,
+    SnaxTag,
+};
+
+struct RenderInput {
+    context: Ident,
+    item: SnaxItem,
+    target: Ident,
+}
+
+impl Parse for RenderInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let context = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let item = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let target = input.parse()?;
+
+        Ok(RenderInput { context, item, target })
+    }
+}
+
+#[test]
+fn embedded_in_grammar() {
+    let input = quote!(ctx, <div class="a"><span /></div> => target);
+    let output: RenderInput = syn::parse2(input).unwrap();
+
+    assert_eq!(output.context.to_string(), "ctx");
+    assert_eq!(output.target.to_string(), "target");
+    assert_eq!(output.item, snax::parse(quote!(<div class="a"><span /></div>)).unwrap());
+}
+
+#[test]
+fn specific_node_types() {
+    let tag: SnaxTag = syn::parse2(quote!(<div></div>)).unwrap();
+    assert_eq!(tag.name.to_string(), "div");
+
+    let tag: SnaxSelfClosingTag = syn::parse2(quote!(<br />)).unwrap();
+    assert_eq!(tag.name.to_string(), "br");
+
+    assert!(syn::parse2::<SnaxTag>(quote!(<br />)).is_err());
+}
+
+#[test]
+fn attributes() {
+    let parser = |input: ParseStream| {
+        let mut attributes = Vec::new();
+
+        while !input.is_empty() {
+            attributes.push(input.parse::<SnaxAttribute>()?);
+        }
+
+        Ok(attributes)
+    };
+
+    let attributes = parser.parse2(quote!(foo="bar" disabled {..props})).unwrap();
+    assert_eq!(attributes.len(), 3);
+}
+
+#[test]
+fn errors_are_spanned() {
+    let error = syn::parse2::<SnaxItem>(quote!(<div></span>)).unwrap_err();
+    let messages: Vec<String> = error.into_iter().map(|error| error.to_string()).collect();
+
+    assert_eq!(messages, vec![
+        "closing tag `</span>` does not match opening tag `<div>`",
+        "opening tag `<div>` is here",
+    ]);
+}
@@ -1,12 +1,21 @@
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenTree};
 use quote::quote;
 
 use snax::{
+    AttributeValue,
+    ParseError,
     SnaxItem,
     SnaxTag,
     SnaxSelfClosingTag,
     SnaxAttribute,
     SnaxFragment,
+    SnaxName,
+    SnaxNameSeparator,
+    SnaxPath,
+    SnaxIf,
+    SnaxFor,
+    SnaxMatch,
+    SnaxMatchArm,
 };
 
 /// Like quote!, but returns a single TokenTree instead
@@ -16,6 +25,17 @@ macro_rules! quote_one {
     };
 }
 
+/// Like quote_one!, but returns an AttributeValue for a literal or a block
+macro_rules! value_one {
+    ($($value: tt)*) => {
+        match quote_one!($($value)*) {
+            TokenTree::Literal(literal) => AttributeValue::from(literal),
+            TokenTree::Group(group) => AttributeValue::from(group),
+            other => panic!("expected a literal or a block, got {}", other),
+        }
+    };
+}
+
 #[test]
 fn just_string() {
     let input = quote!("hello");
@@ -40,9 +60,12 @@ fn empty_div() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -54,8 +77,10 @@ fn self_closing_div() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
+        span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -67,9 +92,12 @@ fn empty_div_comment() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -82,6 +110,8 @@ fn empty_fragment() {
 
     let expected = SnaxItem::Fragment(SnaxFragment {
         children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -93,18 +123,21 @@ fn empty_with_literal_attributes() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: vec![
             SnaxAttribute::Simple {
-                name: Ident::new("foo", Span::call_site()),
-                value: quote_one!("bar"),
+                name: Ident::new("foo", Span::call_site()).into(),
+                value: value_one!("bar"),
             },
             SnaxAttribute::Simple {
-                name: Ident::new("baz", Span::call_site()),
-                value: quote_one!("qux"),
+                name: Ident::new("baz", Span::call_site()).into(),
+                value: value_one!("qux"),
             },
         ],
         children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -116,14 +149,17 @@ fn empty_with_block_attribute() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("label", Span::call_site()),
+        name: Ident::new("label", Span::call_site()).into(),
+        generics: None,
         attributes: vec![
             SnaxAttribute::Simple {
-                name: Ident::new("sum", Span::call_site()),
-                value: quote_one!({ 5 + 5 }),
+                name: Ident::new("sum", Span::call_site()).into(),
+                value: value_one!({ 5 + 5 }),
             },
         ],
         children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -135,17 +171,19 @@ fn self_closing_with_literal_attributes() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: vec![
             SnaxAttribute::Simple {
-                name: Ident::new("foo", Span::call_site()),
-                value: quote_one!("bar"),
+                name: Ident::new("foo", Span::call_site()).into(),
+                value: value_one!("bar"),
             },
             SnaxAttribute::Simple {
-                name: Ident::new("baz", Span::call_site()),
-                value: quote_one!("qux"),
+                name: Ident::new("baz", Span::call_site()).into(),
+                value: value_one!("qux"),
             },
         ],
+        span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -157,13 +195,15 @@ fn self_closing_with_block_attribute() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
-        name: Ident::new("label", Span::call_site()),
+        name: Ident::new("label", Span::call_site()).into(),
+        generics: None,
         attributes: vec![
             SnaxAttribute::Simple {
-                name: Ident::new("sum", Span::call_site()),
-                value: quote_one!({ 5 + 5 }),
+                name: Ident::new("sum", Span::call_site()).into(),
+                value: value_one!({ 5 + 5 }),
             },
         ],
+        span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -179,15 +219,21 @@ fn nested_tags() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: vec![
             SnaxItem::Tag(SnaxTag {
-                name: Ident::new("span", Span::call_site()),
+                name: Ident::new("span", Span::call_site()).into(),
+                generics: None,
                 attributes: Default::default(),
                 children: Default::default(),
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
             }),
         ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -204,20 +250,29 @@ fn adjacent_tags() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: vec![
             SnaxItem::Tag(SnaxTag {
-                name: Ident::new("span", Span::call_site()),
+                name: Ident::new("span", Span::call_site()).into(),
+                generics: None,
                 attributes: Default::default(),
                 children: Default::default(),
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
             }),
             SnaxItem::Tag(SnaxTag {
-                name: Ident::new("div", Span::call_site()),
+                name: Ident::new("div", Span::call_site()).into(),
+                generics: None,
                 attributes: Default::default(),
                 children: Default::default(),
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
             }),
         ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -236,16 +291,24 @@ fn adjacent_tags_in_fragment() {
     let expected = SnaxItem::Fragment(SnaxFragment {
         children: vec![
             SnaxItem::Tag(SnaxTag {
-                name: Ident::new("span", Span::call_site()),
+                name: Ident::new("span", Span::call_site()).into(),
+                generics: None,
                 attributes: Default::default(),
                 children: Default::default(),
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
             }),
             SnaxItem::Tag(SnaxTag {
-                name: Ident::new("div", Span::call_site()),
+                name: Ident::new("div", Span::call_site()).into(),
+                generics: None,
                 attributes: Default::default(),
                 children: Default::default(),
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
             }),
         ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -261,14 +324,19 @@ fn nested_tags_self_closing() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: vec![
             SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
-                name: Ident::new("span", Span::call_site()),
+                name: Ident::new("span", Span::call_site()).into(),
+                generics: None,
                 attributes: Default::default(),
+                span: Span::call_site(),
             }),
         ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -284,11 +352,14 @@ fn content_literal_string() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: vec![
             SnaxItem::Content(quote_one!("Hello, world!")),
         ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -304,11 +375,14 @@ fn content_literal_number() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: vec![
             SnaxItem::Content(quote_one!(5)),
         ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
     assert_eq!(output, expected);
@@ -324,12 +398,898 @@ fn content_block() {
     let output = snax::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
         attributes: Default::default(),
         children: vec![
             SnaxItem::Content(quote_one!({ format!("{} + {} = {}", 1, 2, 3) })),
         ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+#[test]
+fn self_closing_with_spread_attribute() {
+    let input = quote!(<Button {..props} label="x" />);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: Ident::new("Button", Span::call_site()).into(),
+        generics: None,
+        attributes: vec![
+            SnaxAttribute::Spread {
+                value: quote!(props),
+                span: Span::call_site(),
+            },
+            SnaxAttribute::Simple {
+                name: Ident::new("label", Span::call_site()).into(),
+                value: value_one!("x"),
+            },
+        ],
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn jsx_style_spread_attribute() {
+    let input = quote!(<div id="a" {...self.props}></div>);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: Ident::new("id", Span::call_site()).into(),
+                value: value_one!("a"),
+            },
+            SnaxAttribute::Spread {
+                value: quote!(self.props),
+                span: Span::call_site(),
+            },
+        ],
+        children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn spread_attribute_without_value() {
+    let input = quote!(<div {..} />);
+
+    assert!(snax::parse(input).is_err());
+}
+
+#[test]
+fn spread_attribute_with_too_many_dots() {
+    let input = quote!(<div {....props} />);
+
+    match snax::parse(input).unwrap_err() {
+        ParseError::UnexpectedToken { .. } => {},
+        other => panic!("expected ParseError::UnexpectedToken, got {:?}", other),
+    }
+}
+
+#[test]
+fn self_closing_with_flag_attributes() {
+    let input = quote!(<input disabled checked />);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: Ident::new("input", Span::call_site()).into(),
+        generics: None,
+        attributes: vec![
+            SnaxAttribute::Flag {
+                name: Ident::new("disabled", Span::call_site()).into(),
+            },
+            SnaxAttribute::Flag {
+                name: Ident::new("checked", Span::call_site()).into(),
+            },
+        ],
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn flag_attribute_before_close() {
+    let input = quote!(<option value="a" selected>"A"</option>);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: Ident::new("option", Span::call_site()).into(),
+        generics: None,
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: Ident::new("value", Span::call_site()).into(),
+                value: value_one!("a"),
+            },
+            SnaxAttribute::Flag {
+                name: Ident::new("selected", Span::call_site()).into(),
+            },
+        ],
+        children: vec![
+            SnaxItem::Content(quote_one!("A")),
+        ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn hyphenated_names() {
+    let input = quote!(<my-widget data-id="1" aria-hidden={true}></my-widget>);
+    let output = snax::parse(input).unwrap();
+
+    let tag = match output {
+        SnaxItem::Tag(tag) => tag,
+        _ => panic!("expected a tag, got {:?}", output),
+    };
+
+    assert_eq!(tag.name.to_string(), "my-widget");
+
+    let attribute_names: Vec<String> = tag.attributes.iter()
+        .map(|attribute| match attribute {
+            SnaxAttribute::Simple { name, .. } => name.to_string(),
+            _ => panic!("expected a simple attribute, got {:?}", attribute),
+        })
+        .collect();
+
+    assert_eq!(attribute_names, vec!["data-id", "aria-hidden"]);
+}
+
+#[test]
+fn namespaced_names() {
+    let input = quote!(<svg:use xlink:href="#icon" xml:lang="en" />);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: SnaxPath::from(SnaxName {
+            first: Ident::new("svg", Span::call_site()),
+            rest: vec![(SnaxNameSeparator::Colon, Ident::new("use", Span::call_site()))],
+        }),
+        generics: None,
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: SnaxName {
+                    first: Ident::new("xlink", Span::call_site()),
+                    rest: vec![(SnaxNameSeparator::Colon, Ident::new("href", Span::call_site()))],
+                },
+                value: value_one!("#icon"),
+            },
+            SnaxAttribute::Simple {
+                name: SnaxName {
+                    first: Ident::new("xml", Span::call_site()),
+                    rest: vec![(SnaxNameSeparator::Colon, Ident::new("lang", Span::call_site()))],
+                },
+                value: value_one!("en"),
+            },
+        ],
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn path_tags() {
+    let input = quote!(
+        <ui::Button>
+            <crate::widgets::Icon />
+            <self::Row></self::Row>
+            <super::Card />
+        </ui::Button>
+    );
+    let output = snax::parse(input).unwrap();
+
+    let tag = match output {
+        SnaxItem::Tag(tag) => tag,
+        _ => panic!("expected a tag, got {:?}", output),
+    };
+
+    assert_eq!(tag.name.to_string(), "ui::Button");
+    assert!(tag.name.as_name().is_none());
+
+    let child_names: Vec<String> = tag.children.iter()
+        .map(|child| match child {
+            SnaxItem::Tag(tag) => tag.name.to_string(),
+            SnaxItem::SelfClosingTag(tag) => tag.name.to_string(),
+            _ => panic!("expected a tag, got {:?}", child),
+        })
+        .collect();
+
+    assert_eq!(child_names, vec!["crate::widgets::Icon", "self::Row", "super::Card"]);
+}
+
+#[test]
+fn path_tags_must_match() {
+    let input = quote!(<a::X></b::X>);
+
+    match snax::parse(input) {
+        Err(ParseError::MismatchedCloseTag { open, close, .. }) => {
+            assert_eq!(open.to_string(), "a::X");
+            assert_eq!(close.to_string(), "b::X");
+        },
+        other => panic!("expected MismatchedCloseTag, got {:?}", other),
+    }
+}
+
+#[test]
+fn generic_self_closing_tag() {
+    let input = quote!(<List::<User> items={users} />);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: Ident::new("List", Span::call_site()).into(),
+        generics: Some(quote!(User)),
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: Ident::new("items", Span::call_site()).into(),
+                value: value_one!({users}),
+            },
+        ],
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn generic_tag_nested_arguments() {
+    let input = quote!(
+        <table::Table::<Vec<Option<Row>>, fn(u8) -> u8>></table::Table::<Vec<Option<Row>>, fn(u8) -> u8>>
+    );
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxPath {
+            first: Ident::new("table", Span::call_site()).into(),
+            rest: vec![Ident::new("Table", Span::call_site()).into()],
+        },
+        generics: Some(quote!(Vec<Option<Row>>, fn(u8) -> u8)),
+        attributes: Default::default(),
+        children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn generic_tag_closed_without_arguments() {
+    let input = quote!(<List::<User>></List>);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: Ident::new("List", Span::call_site()).into(),
+        generics: Some(quote!(User)),
+        attributes: Default::default(),
+        children: Default::default(),
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn generic_tag_arguments_must_match() {
+    let input = quote!(<List::<User>></List::<Post>>);
+    let error = snax::parse(input).unwrap_err();
+
+    assert_eq!(error.to_string(), "closing tag `</List::<Post>>` does not match opening tag `<List::<User>>`");
+}
+
+#[test]
+fn mismatched_close_tag() {
+    let input = quote!(<div></span>);
+
+    match snax::parse(input) {
+        Err(ParseError::MismatchedCloseTag { open, close, .. }) => {
+            assert_eq!(open.to_string(), "div");
+            assert_eq!(close.to_string(), "span");
+        },
+        other => panic!("expected MismatchedCloseTag, got {:?}", other),
+    }
+}
+
+#[test]
+fn unclosed_tags() {
+    let input = quote!(<div><><span>"hi");
+
+    match snax::parse(input) {
+        Err(ParseError::UnclosedTag { open }) => {
+            let names: Vec<Option<String>> = open.iter()
+                .map(|name| name.as_ref().map(ToString::to_string))
+                .collect();
+
+            assert_eq!(names, vec![Some("div".to_owned()), None, Some("span".to_owned())]);
+        },
+        other => panic!("expected UnclosedTag, got {:?}", other),
+    }
+}
+
+#[test]
+fn truncated_input_reports_unclosed_tags() {
+    for input in [quote!(<a><b), quote!(<a><b class=), quote!(<a>@if ok)] {
+        match snax::parse(input) {
+            Err(ParseError::UnclosedTag { open }) => {
+                let names: Vec<String> = open.iter().flatten().map(ToString::to_string).collect();
+                assert_eq!(names, vec!["a"]);
+            },
+            other => panic!("expected UnclosedTag, got {:?}", other),
+        }
+    }
+
+    let (_, errors) = snax::parse_recovering(quote!(<a><b));
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+
+    assert_eq!(messages, vec!["input ended before closing `<a>`"]);
+}
+
+fn self_closing(name: &str) -> SnaxItem {
+    SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: Ident::new(name, Span::call_site()).into(),
+        generics: None,
+        attributes: Default::default(),
+        span: Span::call_site(),
+    })
+}
+
+#[test]
+fn if_else_chain() {
+    let input = quote!(
+        <div>
+            @if first { <a /> } else if second { "b" } else { <c /> }
+        </div>
+    );
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::If(SnaxIf {
+        condition: quote!(first),
+        then_branch: vec![self_closing("a")],
+        else_branch: Some(vec![
+            SnaxItem::If(SnaxIf {
+                condition: quote!(second),
+                then_branch: vec![SnaxItem::Content(quote_one!("b"))],
+                else_branch: Some(vec![self_closing("c")]),
+                span: Span::call_site(),
+            }),
+        ]),
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output.children(), &[expected]);
+}
+
+#[test]
+fn for_loop() {
+    let input = quote!(
+        @for (index, user) in users.iter().enumerate() {
+            <li>{ index }</li>
+        }
+    );
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::For(SnaxFor {
+        pattern: quote!((index, user)),
+        iterator: quote!(users.iter().enumerate()),
+        body: vec![
+            SnaxItem::Tag(SnaxTag {
+                name: Ident::new("li", Span::call_site()).into(),
+                generics: None,
+                attributes: Default::default(),
+                children: vec![SnaxItem::Content(quote_one!({ index }))],
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
+            }),
+        ],
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn struct_patterns_in_control_flow() {
+    let input = quote!(
+        <ul>
+            @for Point { x, y } in points { <li>{ x }{ y }</li> }
+            @if let Point { x, .. } = point { <b>{ x }</b> } else if let 1..=5 = n { "small" }
+        </ul>
+    );
+    let output = snax::parse(input).unwrap();
+
+    match output.children() {
+        [SnaxItem::For(for_item), SnaxItem::If(if_item)] => {
+            assert_eq!(for_item.pattern.to_string(), quote!(Point { x, y }).to_string());
+            assert_eq!(for_item.iterator.to_string(), quote!(points).to_string());
+            assert_eq!(if_item.condition.to_string(), quote!(let Point { x, .. } = point).to_string());
+
+            match if_item.else_branch.as_deref() {
+                Some([SnaxItem::If(else_if)]) => {
+                    assert_eq!(else_if.condition.to_string(), quote!(let 1..=5 = n).to_string());
+                },
+                other => panic!("expected an `else if`, got {:?}", other),
+            }
+        },
+        other => panic!("expected `@for` and `@if`, got {:?}", other),
+    }
+}
+
+#[test]
+fn match_arms() {
+    let input = quote!(
+        @match status {
+            Status::Ok(value) if value > 0 => <a />,
+            Status::Ok(_) | Status::Empty => { "empty" <b /> }
+            _ => "error"
+        }
+    );
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::Match(SnaxMatch {
+        expression: quote!(status),
+        arms: vec![
+            SnaxMatchArm {
+                pattern: quote!(Status::Ok(value)),
+                guard: Some(quote!(value > 0)),
+                body: vec![self_closing("a")],
+            },
+            SnaxMatchArm {
+                pattern: quote!(Status::Ok(_) | Status::Empty),
+                guard: None,
+                body: vec![SnaxItem::Content(quote_one!("empty")), self_closing("b")],
+            },
+            SnaxMatchArm {
+                pattern: quote!(_),
+                guard: None,
+                body: vec![SnaxItem::Content(quote_one!("error"))],
+            },
+        ],
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn control_flow_error_messages() {
+    let error = snax::parse(quote!(@while x {})).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `while`, expected `if`, `for`, or `match` after `@`");
+
+    let error = snax::parse(quote!(@if {})).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `{ }`, expected a condition after `if`");
+
+    let error = snax::parse(quote!(@for x in xs)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected end of input, expected a block for the body of `@for`");
+
+    let error = snax::parse(quote!(@if x {} else <a />)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `<`, expected `if` or a block after `else`");
+
+    let error = snax::parse(quote!(@match x { A => <a /> B => <b /> })).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `B`, expected `,` after the match arm");
+}
+
+#[test]
+fn recovering_inside_control_flow() {
+    let input = quote!(
+        <ul>
+            @for x in xs { <li><span foo=></span></li> }
+            @if y { <p></span> }
+            <li />
+        </ul>
+    );
+    let (output, errors) = snax::parse_recovering(input);
+
+    assert_eq!(output.unwrap().children().len(), 3);
+
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(messages, vec![
+        "unexpected `>`, expected a literal, a block, a path, or markup as the attribute value",
+        "closing tag `</span>` does not match opening tag `<p>`",
+        "input ended before closing `<p>`",
+    ]);
+}
+
+#[test]
+fn unbraced_attribute_values() {
+    let input = quote!(<Slider snap=true open=false min=-1 step=-0.5 kind=IconKind::Close value=value />);
+    let output = snax::parse(input).unwrap();
+
+    let attributes = match &output {
+        SnaxItem::SelfClosingTag(tag) => &tag.attributes,
+        other => panic!("expected a self-closing tag, got {:?}", other),
+    };
+
+    let values: Vec<&AttributeValue> = attributes.iter()
+        .map(|attribute| match attribute {
+            SnaxAttribute::Simple { value, .. } => value,
+            other => panic!("expected a simple attribute, got {:?}", other),
+        })
+        .collect();
+
+    assert!(matches!(values[0], AttributeValue::Bool { value: true, .. }));
+    assert!(matches!(values[1], AttributeValue::Bool { value: false, .. }));
+    assert!(matches!(values[2], AttributeValue::Negative { .. }));
+    assert!(matches!(values[3], AttributeValue::Negative { .. }));
+    assert!(matches!(values[4], AttributeValue::Path { rest, .. } if rest.len() == 1));
+    assert!(matches!(values[5], AttributeValue::Path { rest, .. } if rest.is_empty()));
+
+    let written: Vec<String> = values.iter().map(ToString::to_string).collect();
+    assert_eq!(written, vec!["true", "false", "-1", "-0.5", "IconKind::Close", "value"]);
+
+    let expressions: Vec<String> = values.iter().map(|value| value.expression().to_string()).collect();
+    assert_eq!(expressions[4], quote!(IconKind::Close).to_string());
+}
+
+#[test]
+fn unbraced_attribute_value_errors() {
+    let error = snax::parse(quote!(<Slider min=-"a" />)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `\"a\"`, expected a number after `-`");
+
+    let error = snax::parse(quote!(<Icon kind=IconKind:: />)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `/`, expected a name after `::`");
+
+    let error = snax::parse(quote!(<Icon kind=+ />)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `+`, expected a literal, a block, a path, or markup as the attribute value");
+}
+
+#[test]
+fn shorthand_attributes() {
+    let input = quote!(<Input {value} {on_change} {..rest} />);
+    let output = snax::parse(input).unwrap();
+
+    let expected = snax::parse(quote!(<Input value=value on_change=on_change {..rest} />)).unwrap();
+    assert_eq!(output, expected);
+
+    match &output {
+        SnaxItem::SelfClosingTag(tag) => {
+            assert_eq!(tag.attribute("value").unwrap().to_string(), "value");
+            assert!(matches!(&tag.attributes[2], SnaxAttribute::Spread { .. }));
+        },
+        other => panic!("expected a self-closing tag, got {:?}", other),
+    }
+}
+
+#[test]
+fn shorthand_raw_identifiers() {
+    let output = snax::parse(quote!(<input {r#type} />)).unwrap();
+
+    match &output {
+        SnaxItem::SelfClosingTag(tag) => match &tag.attributes[0] {
+            SnaxAttribute::Simple { name, value } => {
+                assert_eq!(name.to_string(), "type");
+                assert_eq!(value.to_string(), "r#type");
+            },
+            other => panic!("expected a simple attribute, got {:?}", other),
+        },
+        other => panic!("expected a self-closing tag, got {:?}", other),
+    }
+}
+
+#[test]
+fn shorthand_attribute_errors() {
+    let expected = "an attribute, a spread like `{..props}`, or shorthand like `{value}`";
+
+    for input in [quote!(<Input {value.0} />), quote!(<Input {true} />), quote!(<Input {.value} />)] {
+        match snax::parse(input).unwrap_err() {
+            ParseError::UnexpectedToken { expected: actual, .. } => assert_eq!(actual, expected),
+            other => panic!("expected ParseError::UnexpectedToken, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn markup_attributes() {
+    let input = quote!(<Layout header=<Header /> footer=<>"Footer"</> wide />);
+    let output = snax::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: Ident::new("Layout", Span::call_site()).into(),
+        generics: None,
+        attributes: vec![
+            SnaxAttribute::Markup {
+                name: Ident::new("header", Span::call_site()).into(),
+                value: Box::new(self_closing("Header")),
+            },
+            SnaxAttribute::Markup {
+                name: Ident::new("footer", Span::call_site()).into(),
+                value: Box::new(SnaxItem::Fragment(SnaxFragment {
+                    children: vec![SnaxItem::Content(quote_one!("Footer"))],
+                    open_span: Span::call_site(),
+                    close_span: Span::call_site(),
+                })),
+            },
+            SnaxAttribute::Flag {
+                name: Ident::new("wide", Span::call_site()).into(),
+            },
+        ],
+        span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn nested_markup_attributes() {
+    let input = quote!(
+        <Page header=<Header title=<b>"Home"</b> />>"Body"</Page>
+    );
+    let output = snax::parse(input).unwrap();
+
+    let header = match &output {
+        SnaxItem::Tag(tag) => match &tag.attributes[0] {
+            SnaxAttribute::Markup { value, .. } => value,
+            other => panic!("expected a markup attribute, got {:?}", other),
+        },
+        other => panic!("expected a tag, got {:?}", other),
+    };
+
+    assert_eq!(**header, snax::parse(quote!(<Header title=<b>"Home"</b> />)).unwrap());
+    assert_eq!(output.children(), &[SnaxItem::Content(quote_one!("Body"))]);
+}
+
+#[test]
+fn markup_attribute_errors() {
+    let error = snax::parse(quote!(<Layout header=<Header></Footer> />)).unwrap_err();
+    assert_eq!(error.to_string(), "closing tag `</Footer>` does not match opening tag `<Header>`");
+
+    let error = snax::parse(quote!(<Layout header=<Header foo=> />)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `>`, expected a literal, a block, a path, or markup as the attribute value");
+}
+
+#[test]
+fn error_messages() {
+    let error = snax::parse(quote!(<div foo=>)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected `>`, expected a literal, a block, a path, or markup as the attribute value");
+
+    let error = snax::parse(quote!(<div)).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected end of input, expected an attribute, `>`, or `/>`");
+
+    let error = snax::parse(quote!(<div></span>)).unwrap_err();
+    assert_eq!(error.to_string(), "closing tag `</span>` does not match opening tag `<div>`");
+
+    let error = snax::parse(quote!(<div><>)).unwrap_err();
+    assert_eq!(error.to_string(), "input ended before closing `<div>`, `<>`");
+}
+
+#[test]
+fn error_to_compile_error() {
+    let error = snax::parse(quote!(<div / div>)).unwrap_err();
+    let expected = quote!(::core::compile_error! { "unexpected `div`, expected `>` after `/`" });
+
+    assert_eq!(error.to_compile_error().to_string(), expected.to_string());
+}
+
+#[test]
+fn mismatched_tag_to_compile_error() {
+    let error = snax::parse(quote!(<div></span>)).unwrap_err();
+    let expected = quote!(::core::compile_error! { "closing tag `</span>` does not match opening tag `<div>`" });
+
+    assert_eq!(error.to_compile_error().to_string(), expected.to_string());
+}
+
+#[test]
+fn recovering_reports_every_error() {
+    let input = quote!(
+        <div>
+            <span foo=></span>
+            <p>
+        </div>
+    );
+    let (output, errors) = snax::parse_recovering(input);
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
+        attributes: Default::default(),
+        children: vec![
+            SnaxItem::Tag(SnaxTag {
+                name: Ident::new("span", Span::call_site()).into(),
+                generics: None,
+                attributes: Default::default(),
+                children: Default::default(),
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
+            }),
+            SnaxItem::Tag(SnaxTag {
+                name: Ident::new("p", Span::call_site()).into(),
+                generics: None,
+                attributes: Default::default(),
+                children: Default::default(),
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
+            }),
+        ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
     });
 
+    assert_eq!(output, Some(expected));
+
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(messages, vec![
+        "unexpected `>`, expected a literal, a block, a path, or markup as the attribute value",
+        "closing tag `</div>` does not match opening tag `<p>`",
+    ]);
+}
+
+#[test]
+fn recovering_keeps_tags_with_bad_attributes() {
+    let (output, errors) = snax::parse_recovering(quote!(<div x= ><span 5></span></div>));
+
+    assert_eq!(output, Some(snax::parse(quote!(<div><span></span></div>)).unwrap()));
+
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(messages, vec![
+        "unexpected `>`, expected a literal, a block, a path, or markup as the attribute value",
+        "unexpected `5`, expected an attribute, `>`, or `/>`",
+    ]);
+
+    let (output, errors) = snax::parse_recovering(quote!(<ul><li a=+ class="x" /></ul>));
+
+    assert_eq!(output, Some(snax::parse(quote!(<ul><li /></ul>)).unwrap()));
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn recovering_stray_closing_tag() {
+    let (output, errors) = snax::parse_recovering(quote!(</a>));
+
+    assert_eq!(output, None);
+
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(messages, vec!["unexpected `</a>`"]);
+}
+
+#[test]
+fn recovering_closes_dangling_tags() {
+    let input = quote!(<div><span>"hi");
+    let (output, errors) = snax::parse_recovering(input);
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: Ident::new("div", Span::call_site()).into(),
+        generics: None,
+        attributes: Default::default(),
+        children: vec![
+            SnaxItem::Tag(SnaxTag {
+                name: Ident::new("span", Span::call_site()).into(),
+                generics: None,
+                attributes: Default::default(),
+                children: vec![
+                    SnaxItem::Content(quote_one!("hi")),
+                ],
+                open_span: Span::call_site(),
+                close_span: Span::call_site(),
+            }),
+        ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
+    });
+
+    assert_eq!(output, Some(expected));
+    assert_eq!(errors.len(), 1);
+
+    match &errors[0] {
+        ParseError::UnclosedTag { open } => assert_eq!(open.len(), 2),
+        other => panic!("expected UnclosedTag, got {:?}", other),
+    }
+}
+
+#[test]
+fn recovering_without_any_item() {
+    let (output, errors) = snax::parse_recovering(quote!(foo bar));
+
+    assert_eq!(output, None);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn recovering_valid_input() {
+    let (output, errors) = snax::parse_recovering(quote!(<div />));
+
+    assert!(output.is_some());
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn many_root_items() {
+    let input = quote!(
+        <li />
+        <li>"Two"</li>
+        { three }
+        <></>
+    );
+    let output = snax::parse_many(input).unwrap();
+
+    let expected = vec![
+        SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+            name: Ident::new("li", Span::call_site()).into(),
+            generics: None,
+            attributes: Default::default(),
+            span: Span::call_site(),
+        }),
+        SnaxItem::Tag(SnaxTag {
+            name: Ident::new("li", Span::call_site()).into(),
+            generics: None,
+            attributes: Default::default(),
+            children: vec![
+                SnaxItem::Content(quote_one!("Two")),
+            ],
+            open_span: Span::call_site(),
+            close_span: Span::call_site(),
+        }),
+        SnaxItem::Content(quote_one!({ three })),
+        SnaxItem::Fragment(SnaxFragment {
+            children: Default::default(),
+            open_span: Span::call_site(),
+            close_span: Span::call_site(),
+        }),
+    ];
+
     assert_eq!(output, expected);
-}
\ No newline at end of file
+}
+
+#[test]
+fn many_root_items_empty() {
+    let output = snax::parse_many(quote!()).unwrap();
+
+    assert!(output.is_empty());
+}
+
+#[test]
+fn many_root_items_unclosed() {
+    let input = quote!(<li /> <li>);
+
+    assert!(snax::parse_many(input).is_err());
+}
+
+#[test]
+fn prefix_returns_remainder() {
+    let input = quote!(<App title="hi"><Child /></App> => target, 5);
+    let (output, rest) = snax::parse_prefix(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: Ident::new("App", Span::call_site()).into(),
+        generics: None,
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: Ident::new("title", Span::call_site()).into(),
+                value: value_one!("hi"),
+            },
+        ],
+        children: vec![
+            SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+                name: Ident::new("Child", Span::call_site()).into(),
+                generics: None,
+                attributes: Default::default(),
+                span: Span::call_site(),
+            }),
+        ],
+        open_span: Span::call_site(),
+        close_span: Span::call_site(),
+    });
+
+    assert_eq!(output, expected);
+    assert_eq!(rest.to_string(), quote!(=> target, 5).to_string());
+}
+
+#[test]
+fn prefix_without_remainder() {
+    let (output, rest) = snax::parse_prefix(quote!("hello")).unwrap();
+
+    assert_eq!(output, SnaxItem::Content(quote_one!("hello")));
+    assert!(rest.is_empty());
+}
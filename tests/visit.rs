@@ -0,0 +1,162 @@
+use proc_macro2::{Ident, Span};
+use quote::quote;
+
+use snax::{
+    SnaxAttribute,
+    SnaxItem,
+    SnaxName,
+    SnaxPath,
+    SnaxSelfClosingTag,
+    fold::{self, Fold},
+    visit::{self, Visit},
+    visit_mut::{self, VisitMut},
+};
+
+#[test]
+fn visit_collects_names() {
+    #[derive(Default)]
+    struct Names {
+        tags: Vec<String>,
+        attributes: Vec<String>,
+    }
+
+    impl<'ast> Visit<'ast> for Names {
+        fn visit_path(&mut self, path: &'ast SnaxPath) {
+            self.tags.push(path.to_string());
+        }
+
+        fn visit_attribute(&mut self, attribute: &'ast SnaxAttribute) {
+            if let SnaxAttribute::Simple { name, .. } | SnaxAttribute::Flag { name } = attribute {
+                self.attributes.push(name.to_string());
+            }
+
+            visit::visit_attribute(self, attribute);
+        }
+    }
+
+    let item = snax::parse(quote!(
+        <ul class="list">
+            <>
+                <li hidden>"a"</li>
+            </>
+            <ui::Divider {..props} />
+        </ul>
+    )).unwrap();
+
+    let mut names = Names::default();
+    names.visit_item(&item);
+
+    assert_eq!(names.tags, vec!["ul", "li", "ui::Divider"]);
+    assert_eq!(names.attributes, vec!["class", "hidden"]);
+}
+
+#[test]
+fn visit_mut_edits_in_place() {
+    struct AddKey;
+
+    impl VisitMut for AddKey {
+        fn visit_self_closing_tag_mut(&mut self, tag: &mut SnaxSelfClosingTag) {
+            tag.attributes.push(SnaxAttribute::Flag {
+                name: Ident::new("key", Span::call_site()).into(),
+            });
+
+            visit_mut::visit_self_closing_tag_mut(self, tag);
+        }
+    }
+
+    let mut item = snax::parse(quote!(<div><br /><> <hr /> </></div>)).unwrap();
+    AddKey.visit_item_mut(&mut item);
+
+    let expected = snax::parse(quote!(<div><br key /><> <hr key /> </></div>)).unwrap();
+    assert_eq!(item, expected);
+}
+
+#[test]
+fn fold_rebuilds_tree() {
+    struct Rename;
+
+    impl Fold for Rename {
+        fn fold_name(&mut self, name: SnaxName) -> SnaxName {
+            if name.to_string() == "span" {
+                Ident::new("strong", name.span()).into()
+            } else {
+                fold::fold_name(self, name)
+            }
+        }
+    }
+
+    let item = snax::parse(quote!(<p><span class="x">"hi"</span><span /></p>)).unwrap();
+    let item: SnaxItem = Rename.fold_item(item);
+
+    let expected = snax::parse(quote!(<p><strong class="x">"hi"</strong><strong /></p>)).unwrap();
+    assert_eq!(item, expected);
+}
+
+#[test]
+fn visitors_walk_control_flow() {
+    struct Rename;
+
+    impl Fold for Rename {
+        fn fold_name(&mut self, name: SnaxName) -> SnaxName {
+            if name.to_string() == "span" {
+                Ident::new("strong", name.span()).into()
+            } else {
+                fold::fold_name(self, name)
+            }
+        }
+    }
+
+    let item = snax::parse(quote!(
+        <p>
+            @if a { <span /> } else { <span /> }
+            @for x in xs { <span /> }
+            @match x { _ => <span /> }
+        </p>
+    )).unwrap();
+    let item: SnaxItem = Rename.fold_item(item);
+
+    let expected = snax::parse(quote!(
+        <p>
+            @if a { <strong /> } else { <strong /> }
+            @for x in xs { <strong /> }
+            @match x { _ => <strong /> }
+        </p>
+    )).unwrap();
+    assert_eq!(item, expected);
+
+    #[derive(Default)]
+    struct Count(usize);
+
+    impl<'ast> Visit<'ast> for Count {
+        fn visit_path(&mut self, _path: &'ast SnaxPath) {
+            self.0 += 1;
+        }
+    }
+
+    let mut count = Count::default();
+    count.visit_item(&item);
+    assert_eq!(count.0, 5);
+}
+
+#[test]
+fn visit_walks_embedded_markup() {
+    #[derive(Default)]
+    struct Names(Vec<String>);
+
+    impl<'ast> Visit<'ast> for Names {
+        fn visit_path(&mut self, path: &'ast SnaxPath) {
+            self.0.push(path.to_string());
+        }
+    }
+
+    let item = snax::parse_embedded(quote!(
+        <Table row={ |x| <Row /> }>
+            { items.iter().map(|item| <li>{ item }</li>) }
+        </Table>
+    )).unwrap();
+
+    let mut names = Names::default();
+    names.visit_item(&item);
+
+    assert_eq!(names.0, vec!["Table", "Row", "li"]);
+}